@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
 use std::env;
+use std::str::FromStr;
 
 /// Bot configuration structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -7,6 +9,8 @@ pub struct BotConfig {
     // Solana Configuration
     pub rpc_url: String,
     pub ws_url: Option<String>,
+    pub simulation_rpc_url: Option<String>,
+    pub blockhash_refresh_interval_ms: u64,
 
     // Wallet Configuration
     pub private_key: Option<String>,
@@ -14,16 +18,36 @@ pub struct BotConfig {
 
     // Trading Configuration
     pub buy_amount_sol: f64,
+    pub buy_sizing: BuySizing,
     pub min_liquidity: f64,
     pub max_slippage: f64,
+    pub base_slippage: f64,
+    pub slippage_size_factor: f64,
+    pub max_price_impact_percentage: f64,
     pub take_profit_percentage: f64,
     pub stop_loss_percentage: f64,
     pub trailing_stop_loss_percentage: f64,
+    pub min_hold_time_ms: u64,
+    pub position_store_path: Option<String>,
 
     // Safety Settings
     pub trading_cooldown_ms: u64,
     pub max_loss_per_trade_sol: f64,
+    pub max_sol_per_mint: Option<f64>,
+    pub max_drawdown_sol: f64,
     pub max_trades_per_hour: u32,
+    pub max_trades_per_minute: u32,
+    pub max_concurrent_trades: usize,
+    pub token_event_ttl_ms: u64,
+    pub signature_dedup_window: usize,
+    pub min_fill_ratio: f64,
+    pub min_sol_reserve: f64,
+    pub rpc_failure_threshold: u32,
+    pub rpc_failure_window_secs: u64,
+    pub rpc_breaker_cooldown_secs: u64,
+    pub deadman_file: Option<String>,
+    pub deadman_interval_secs: u64,
+    pub deadman_liquidate: bool,
 
     // Token Filtering
     pub min_market_cap: f64,
@@ -32,15 +56,38 @@ pub struct BotConfig {
     pub max_holders: u32,
     pub require_social_links: bool,
     pub require_creator_verification: bool,
+    pub mint_allowlist: Vec<Pubkey>,
+    pub mint_denylist: Vec<Pubkey>,
+    pub program_denylist: Vec<Pubkey>,
 
     // Gas Optimization
+    pub priority_fee_api_url: Option<String>,
     pub priority_fee_lamports: u64,
     pub max_priority_fee_lamports: u64,
+    pub sell_priority_fee_lamports: Option<u64>,
+    pub sell_compute_unit_limit: u32,
+
+    // Execution
+    pub resimulate_before_send: bool,
+    pub resimulate_min_sol: f64,
+    pub confirmation_timeout_ms: u64,
+    pub confirmation_poll_interval_ms: u64,
+    pub close_empty_atas_on_shutdown: bool,
+    pub max_latency_budget_ms: u64,
+    pub retry_on_slippage: bool,
+    pub slippage_retry_size_factor: f64,
+    pub max_realized_slippage_bps: u64,
+    pub realized_slippage_breach_limit: u32,
+    pub pause_on_realized_slippage: bool,
 
     // Monitoring
     pub log_level: String,
+    pub log_json: bool,
     pub telegram_bot_token: Option<String>,
     pub telegram_chat_id: Option<String>,
+    pub dump_failed_tx_dir: Option<String>,
+    pub health_check_interval_secs: u64,
+    pub monitor_inactivity_timeout_secs: u64,
 
     // Simulation Mode
     pub simulation_mode: bool,
@@ -52,6 +99,8 @@ impl Default for BotConfig {
             // Solana Configuration
             rpc_url: "https://api.mainnet-beta.solana.com".to_string(),
             ws_url: None,
+            simulation_rpc_url: None,
+            blockhash_refresh_interval_ms: 2000,
 
             // Wallet Configuration
             private_key: None,
@@ -59,16 +108,36 @@ impl Default for BotConfig {
 
             // Trading Configuration
             buy_amount_sol: 0.1,
+            buy_sizing: BuySizing::Absolute(0.1),
             min_liquidity: 5.0,
             max_slippage: 25.0,
+            base_slippage: 5.0,
+            slippage_size_factor: 100.0,
+            max_price_impact_percentage: 10.0,
             take_profit_percentage: 100.0,
             stop_loss_percentage: 30.0,
             trailing_stop_loss_percentage: 10.0,
+            min_hold_time_ms: 0,
+            position_store_path: None,
 
             // Safety Settings
             trading_cooldown_ms: 5000,
             max_loss_per_trade_sol: 0.5,
+            max_sol_per_mint: None,
+            max_drawdown_sol: 2.0,
             max_trades_per_hour: 10,
+            max_trades_per_minute: 5,
+            max_concurrent_trades: 1,
+            token_event_ttl_ms: 2000,
+            signature_dedup_window: 10_000,
+            min_fill_ratio: 0.5,
+            min_sol_reserve: 0.05,
+            rpc_failure_threshold: 5,
+            rpc_failure_window_secs: 30,
+            rpc_breaker_cooldown_secs: 30,
+            deadman_file: None,
+            deadman_interval_secs: 300,
+            deadman_liquidate: false,
 
             // Token Filtering
             min_market_cap: 1000.0,
@@ -77,15 +146,38 @@ impl Default for BotConfig {
             max_holders: 1000,
             require_social_links: false,
             require_creator_verification: false,
+            mint_allowlist: Vec::new(),
+            mint_denylist: Vec::new(),
+            program_denylist: Vec::new(),
 
             // Gas Optimization
+            priority_fee_api_url: None,
             priority_fee_lamports: 10000,
             max_priority_fee_lamports: 100000,
+            sell_priority_fee_lamports: None,
+            sell_compute_unit_limit: constants::COMPUTE_UNIT_LIMIT,
+
+            // Execution
+            resimulate_before_send: false,
+            resimulate_min_sol: 0.5,
+            confirmation_timeout_ms: 30_000,
+            confirmation_poll_interval_ms: 500,
+            close_empty_atas_on_shutdown: false,
+            max_latency_budget_ms: 3000,
+            retry_on_slippage: false,
+            slippage_retry_size_factor: 0.5,
+            max_realized_slippage_bps: 1000,
+            realized_slippage_breach_limit: 3,
+            pause_on_realized_slippage: false,
 
             // Monitoring
             log_level: "info".to_string(),
+            log_json: false,
             telegram_bot_token: None,
             telegram_chat_id: None,
+            dump_failed_tx_dir: None,
+            health_check_interval_secs: 60,
+            monitor_inactivity_timeout_secs: 120,
 
             // Simulation Mode
             simulation_mode: true,
@@ -93,7 +185,13 @@ impl Default for BotConfig {
     }
 }
 
+/// Prefix that takes precedence over the bare variable name
+const ENV_PREFIX: &str = "BOT_";
+
 /// Load configuration from environment variables
+///
+/// Every field can be set either as `NAME` or as `BOT_NAME`; the prefixed
+/// form wins when both are present.
 pub fn load_config() -> Result<BotConfig, Box<dyn std::error::Error>> {
     // Load .env file if it exists
     dotenv::dotenv().ok();
@@ -101,87 +199,103 @@ pub fn load_config() -> Result<BotConfig, Box<dyn std::error::Error>> {
     let mut config = BotConfig::default();
 
     // Solana Configuration
-    if let Ok(rpc_url) = env::var("RPC_URL") {
-        config.rpc_url = rpc_url;
-    }
-    if let Ok(ws_url) = env::var("WS_URL") {
+    parse_env("RPC_URL", &mut config.rpc_url)?;
+    if let Some(ws_url) = env_var("WS_URL") {
         config.ws_url = Some(ws_url);
     }
+    config.simulation_rpc_url = env_var("SIMULATION_RPC_URL");
+    parse_env("BLOCKHASH_REFRESH_INTERVAL_MS", &mut config.blockhash_refresh_interval_ms)?;
 
     // Wallet Configuration
-    config.private_key = env::var("PRIVATE_KEY").ok();
-    config.main_wallet_private_key = env::var("MAIN_WALLET_PRIVATE_KEY").ok();
+    config.private_key = env_var("PRIVATE_KEY");
+    config.main_wallet_private_key = env_var("MAIN_WALLET_PRIVATE_KEY");
 
     // Trading Configuration
-    if let Ok(val) = env::var("BUY_AMOUNT_SOL") {
-        config.buy_amount_sol = val.parse()?;
-    }
-    if let Ok(val) = env::var("MIN_LIQUIDITY") {
-        config.min_liquidity = val.parse()?;
-    }
-    if let Ok(val) = env::var("MAX_SLIPPAGE") {
-        config.max_slippage = val.parse()?;
-    }
-    if let Ok(val) = env::var("TAKE_PROFIT_PERCENTAGE") {
-        config.take_profit_percentage = val.parse()?;
-    }
-    if let Ok(val) = env::var("STOP_LOSS_PERCENTAGE") {
-        config.stop_loss_percentage = val.parse()?;
-    }
-    if let Ok(val) = env::var("TRAILING_STOP_LOSS_PERCENTAGE") {
-        config.trailing_stop_loss_percentage = val.parse()?;
+    parse_env("BUY_AMOUNT_SOL", &mut config.buy_amount_sol)?;
+    config.buy_sizing = BuySizing::Absolute(config.buy_amount_sol);
+    let mut buy_amount_percentage = None;
+    parse_env_opt("BUY_AMOUNT_PERCENTAGE", &mut buy_amount_percentage)?;
+    if let Some(percentage) = buy_amount_percentage {
+        config.buy_sizing = BuySizing::PctOfBalance(percentage);
     }
+    parse_env("MIN_LIQUIDITY", &mut config.min_liquidity)?;
+    parse_env("MAX_SLIPPAGE", &mut config.max_slippage)?;
+    parse_env("BASE_SLIPPAGE", &mut config.base_slippage)?;
+    parse_env("SLIPPAGE_SIZE_FACTOR", &mut config.slippage_size_factor)?;
+    parse_env("MAX_PRICE_IMPACT_PERCENTAGE", &mut config.max_price_impact_percentage)?;
+    parse_env("TAKE_PROFIT_PERCENTAGE", &mut config.take_profit_percentage)?;
+    parse_env("STOP_LOSS_PERCENTAGE", &mut config.stop_loss_percentage)?;
+    parse_env("TRAILING_STOP_LOSS_PERCENTAGE", &mut config.trailing_stop_loss_percentage)?;
+    parse_env("MIN_HOLD_TIME_MS", &mut config.min_hold_time_ms)?;
+    config.position_store_path = env_var("POSITION_STORE_PATH");
 
     // Safety Settings
-    if let Ok(val) = env::var("TRADING_COOLDOWN_MS") {
-        config.trading_cooldown_ms = val.parse()?;
-    }
-    if let Ok(val) = env::var("MAX_LOSS_PER_TRADE_SOL") {
-        config.max_loss_per_trade_sol = val.parse()?;
-    }
-    if let Ok(val) = env::var("MAX_TRADES_PER_HOUR") {
-        config.max_trades_per_hour = val.parse()?;
-    }
+    parse_env("TRADING_COOLDOWN_MS", &mut config.trading_cooldown_ms)?;
+    parse_env("MAX_LOSS_PER_TRADE_SOL", &mut config.max_loss_per_trade_sol)?;
+    parse_env_opt("MAX_SOL_PER_MINT", &mut config.max_sol_per_mint)?;
+    parse_env("MAX_DRAWDOWN_SOL", &mut config.max_drawdown_sol)?;
+    parse_env("MAX_TRADES_PER_HOUR", &mut config.max_trades_per_hour)?;
+    parse_env("MAX_TRADES_PER_MINUTE", &mut config.max_trades_per_minute)?;
+    parse_env("MAX_CONCURRENT_TRADES", &mut config.max_concurrent_trades)?;
+    parse_env("TOKEN_EVENT_TTL_MS", &mut config.token_event_ttl_ms)?;
+    parse_env("SIGNATURE_DEDUP_WINDOW", &mut config.signature_dedup_window)?;
+    parse_env("MIN_FILL_RATIO", &mut config.min_fill_ratio)?;
+    parse_env("MIN_SOL_RESERVE", &mut config.min_sol_reserve)?;
+    parse_env("RPC_FAILURE_THRESHOLD", &mut config.rpc_failure_threshold)?;
+    parse_env("RPC_FAILURE_WINDOW_SECS", &mut config.rpc_failure_window_secs)?;
+    parse_env("RPC_BREAKER_COOLDOWN_SECS", &mut config.rpc_breaker_cooldown_secs)?;
+    config.deadman_file = env_var("DEADMAN_FILE");
+    parse_env("DEADMAN_INTERVAL_SECS", &mut config.deadman_interval_secs)?;
+    parse_env("DEADMAN_LIQUIDATE", &mut config.deadman_liquidate)?;
 
     // Token Filtering
-    if let Ok(val) = env::var("MIN_MARKET_CAP") {
-        config.min_market_cap = val.parse()?;
-    }
-    if let Ok(val) = env::var("MAX_MARKET_CAP") {
-        config.max_market_cap = val.parse()?;
-    }
-    if let Ok(val) = env::var("MIN_HOLDERS") {
-        config.min_holders = val.parse()?;
+    parse_env("MIN_MARKET_CAP", &mut config.min_market_cap)?;
+    parse_env("MAX_MARKET_CAP", &mut config.max_market_cap)?;
+    parse_env("MIN_HOLDERS", &mut config.min_holders)?;
+    parse_env("MAX_HOLDERS", &mut config.max_holders)?;
+    parse_env("REQUIRE_SOCIAL_LINKS", &mut config.require_social_links)?;
+    parse_env("REQUIRE_CREATOR_VERIFICATION", &mut config.require_creator_verification)?;
+    if let Some(val) = env_var("MINT_ALLOWLIST") {
+        config.mint_allowlist = parse_pubkey_list("MINT_ALLOWLIST", &val)?;
     }
-    if let Ok(val) = env::var("MAX_HOLDERS") {
-        config.max_holders = val.parse()?;
+    if let Some(val) = env_var("MINT_DENYLIST") {
+        config.mint_denylist = parse_pubkey_list("MINT_DENYLIST", &val)?;
     }
-    if let Ok(val) = env::var("REQUIRE_SOCIAL_LINKS") {
-        config.require_social_links = val.parse()?;
-    }
-    if let Ok(val) = env::var("REQUIRE_CREATOR_VERIFICATION") {
-        config.require_creator_verification = val.parse()?;
+    if let Some(val) = env_var("PROGRAM_DENYLIST") {
+        config.program_denylist = parse_pubkey_list("PROGRAM_DENYLIST", &val)?;
     }
 
     // Gas Optimization
-    if let Ok(val) = env::var("PRIORITY_FEE_LAMPORTS") {
-        config.priority_fee_lamports = val.parse()?;
-    }
-    if let Ok(val) = env::var("MAX_PRIORITY_FEE_LAMPORTS") {
-        config.max_priority_fee_lamports = val.parse()?;
-    }
+    config.priority_fee_api_url = env_var("PRIORITY_FEE_API_URL");
+    parse_env("PRIORITY_FEE_LAMPORTS", &mut config.priority_fee_lamports)?;
+    parse_env("MAX_PRIORITY_FEE_LAMPORTS", &mut config.max_priority_fee_lamports)?;
+    parse_env_opt("SELL_PRIORITY_FEE_LAMPORTS", &mut config.sell_priority_fee_lamports)?;
+    parse_env("SELL_COMPUTE_UNIT_LIMIT", &mut config.sell_compute_unit_limit)?;
+
+    // Execution
+    parse_env("RESIMULATE_BEFORE_SEND", &mut config.resimulate_before_send)?;
+    parse_env("RESIMULATE_MIN_SOL", &mut config.resimulate_min_sol)?;
+    parse_env("CONFIRMATION_TIMEOUT_MS", &mut config.confirmation_timeout_ms)?;
+    parse_env("CONFIRMATION_POLL_INTERVAL_MS", &mut config.confirmation_poll_interval_ms)?;
+    parse_env("CLOSE_EMPTY_ATAS_ON_SHUTDOWN", &mut config.close_empty_atas_on_shutdown)?;
+    parse_env("MAX_LATENCY_BUDGET_MS", &mut config.max_latency_budget_ms)?;
+    parse_env("RETRY_ON_SLIPPAGE", &mut config.retry_on_slippage)?;
+    parse_env("SLIPPAGE_RETRY_SIZE_FACTOR", &mut config.slippage_retry_size_factor)?;
+    parse_env("MAX_REALIZED_SLIPPAGE_BPS", &mut config.max_realized_slippage_bps)?;
+    parse_env("REALIZED_SLIPPAGE_BREACH_LIMIT", &mut config.realized_slippage_breach_limit)?;
+    parse_env("PAUSE_ON_REALIZED_SLIPPAGE", &mut config.pause_on_realized_slippage)?;
 
     // Monitoring
-    if let Ok(val) = env::var("LOG_LEVEL") {
-        config.log_level = val;
-    }
-    config.telegram_bot_token = env::var("TELEGRAM_BOT_TOKEN").ok();
-    config.telegram_chat_id = env::var("TELEGRAM_CHAT_ID").ok();
+    parse_env("LOG_LEVEL", &mut config.log_level)?;
+    parse_env("LOG_JSON", &mut config.log_json)?;
+    config.telegram_bot_token = env_var("TELEGRAM_BOT_TOKEN");
+    config.telegram_chat_id = env_var("TELEGRAM_CHAT_ID");
+    config.dump_failed_tx_dir = env_var("DUMP_FAILED_TX_DIR");
+    parse_env("HEALTH_CHECK_INTERVAL_SECS", &mut config.health_check_interval_secs)?;
+    parse_env("MONITOR_INACTIVITY_TIMEOUT_SECS", &mut config.monitor_inactivity_timeout_secs)?;
 
     // Simulation Mode
-    if let Ok(val) = env::var("SIMULATION_MODE") {
-        config.simulation_mode = val.parse()?;
-    }
+    parse_env("SIMULATION_MODE", &mut config.simulation_mode)?;
 
     // Validate configuration
     validate_config(&config)?;
@@ -189,18 +303,250 @@ pub fn load_config() -> Result<BotConfig, Box<dyn std::error::Error>> {
     Ok(config)
 }
 
+/// Read an environment variable, preferring its `BOT_`-prefixed form
+fn env_var(name: &str) -> Option<String> {
+    env::var(format!("{}{}", ENV_PREFIX, name))
+        .or_else(|_| env::var(name))
+        .ok()
+}
+
+/// Parse an environment variable into `target` if it is set
+fn parse_env<T>(name: &str, target: &mut T) -> Result<(), Box<dyn std::error::Error>>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    if let Some(val) = env_var(name) {
+        *target = val
+            .parse()
+            .map_err(|e| format!("Invalid value for {}: '{}' ({})", name, val, e))?;
+    }
+
+    Ok(())
+}
+
+/// Parse an optional environment variable into `target` if it is set
+fn parse_env_opt<T>(name: &str, target: &mut Option<T>) -> Result<(), Box<dyn std::error::Error>>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    if let Some(val) = env_var(name) {
+        *target = Some(
+            val.parse()
+                .map_err(|e| format!("Invalid value for {}: '{}' ({})", name, val, e))?,
+        );
+    }
+
+    Ok(())
+}
+
+/// Parse a comma-separated list of pubkeys
+fn parse_pubkey_list(name: &str, value: &str) -> Result<Vec<Pubkey>, Box<dyn std::error::Error>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            Pubkey::from_str(s)
+                .map_err(|e| format!("{} contains an invalid pubkey '{}': {}", name, s, e).into())
+        })
+        .collect()
+}
+
 /// Validate configuration
 fn validate_config(config: &BotConfig) -> Result<(), Box<dyn std::error::Error>> {
     if !config.simulation_mode && config.private_key.is_none() {
         return Err("PRIVATE_KEY is required when not in simulation mode".into());
     }
 
+    // Solana Configuration
     if config.rpc_url.is_empty() {
         return Err("RPC_URL is required".into());
     }
+    validate_url("RPC_URL", &config.rpc_url, &["http", "https"])?;
+    if let Some(ws_url) = &config.ws_url {
+        validate_url("WS_URL", ws_url, &["ws", "wss"])?;
+    }
+    if let Some(simulation_rpc_url) = &config.simulation_rpc_url {
+        validate_url("SIMULATION_RPC_URL", simulation_rpc_url, &["http", "https"])?;
+    }
+
+    if config.blockhash_refresh_interval_ms == 0 {
+        return Err("BLOCKHASH_REFRESH_INTERVAL_MS must be greater than 0".into());
+    }
 
+    // Wallet Configuration
+    if let Some(private_key) = &config.private_key {
+        validate_private_key("PRIVATE_KEY", private_key)?;
+    }
+    if let Some(private_key) = &config.main_wallet_private_key {
+        validate_private_key("MAIN_WALLET_PRIVATE_KEY", private_key)?;
+    }
+
+    // Trading Configuration
     if config.buy_amount_sol <= 0.0 {
-        return Err("BUY_AMOUNT_SOL must be greater than 0".into());
+        return Err(format!("BUY_AMOUNT_SOL must be greater than 0 (got {})", config.buy_amount_sol).into());
+    }
+    if config.min_liquidity < 0.0 {
+        return Err(format!("MIN_LIQUIDITY must not be negative (got {})", config.min_liquidity).into());
+    }
+    if let BuySizing::PctOfBalance(percentage) = config.buy_sizing {
+        validate_percentage("BUY_AMOUNT_PERCENTAGE", percentage, 100.0)?;
+    }
+    validate_percentage("MAX_SLIPPAGE", config.max_slippage, 100.0)?;
+    validate_percentage("BASE_SLIPPAGE", config.base_slippage, config.max_slippage)?;
+    if config.slippage_size_factor < 0.0 {
+        return Err(format!(
+            "SLIPPAGE_SIZE_FACTOR must not be negative (got {})",
+            config.slippage_size_factor
+        ).into());
+    }
+    validate_percentage("MAX_PRICE_IMPACT_PERCENTAGE", config.max_price_impact_percentage, 100.0)?;
+    if config.take_profit_percentage <= 0.0 {
+        return Err(format!(
+            "TAKE_PROFIT_PERCENTAGE must be greater than 0 (got {})",
+            config.take_profit_percentage
+        ).into());
+    }
+    validate_percentage("STOP_LOSS_PERCENTAGE", config.stop_loss_percentage, 100.0)?;
+    validate_percentage("TRAILING_STOP_LOSS_PERCENTAGE", config.trailing_stop_loss_percentage, 100.0)?;
+
+    // Safety Settings
+    if let Some(max_sol_per_mint) = config.max_sol_per_mint {
+        if max_sol_per_mint <= 0.0 {
+            return Err(format!("MAX_SOL_PER_MINT must be greater than 0 (got {})", max_sol_per_mint).into());
+        }
+        if config.buy_sizing == BuySizing::Absolute(config.buy_amount_sol)
+            && max_sol_per_mint < config.buy_amount_sol
+        {
+            return Err(format!(
+                "MAX_SOL_PER_MINT ({}) must be at least BUY_AMOUNT_SOL ({})",
+                max_sol_per_mint, config.buy_amount_sol
+            ).into());
+        }
+    }
+    if config.max_drawdown_sol <= 0.0 {
+        return Err(format!("MAX_DRAWDOWN_SOL must be greater than 0 (got {})", config.max_drawdown_sol).into());
+    }
+    if config.max_trades_per_minute == 0 {
+        return Err("MAX_TRADES_PER_MINUTE must be greater than 0".into());
+    }
+    if config.max_concurrent_trades == 0 {
+        return Err("MAX_CONCURRENT_TRADES must be greater than 0".into());
+    }
+    if config.signature_dedup_window == 0 {
+        return Err("SIGNATURE_DEDUP_WINDOW must be greater than 0".into());
+    }
+    if config.deadman_file.is_some() && config.deadman_interval_secs == 0 {
+        return Err("DEADMAN_INTERVAL_SECS must be greater than 0".into());
+    }
+    if config.rpc_failure_threshold == 0 {
+        return Err("RPC_FAILURE_THRESHOLD must be greater than 0".into());
+    }
+    if config.min_sol_reserve < 0.0 {
+        return Err(format!("MIN_SOL_RESERVE must not be negative (got {})", config.min_sol_reserve).into());
+    }
+    if !(0.0..=1.0).contains(&config.min_fill_ratio) {
+        return Err(format!("MIN_FILL_RATIO must be between 0 and 1 (got {})", config.min_fill_ratio).into());
+    }
+
+    // Token Filtering
+    if config.min_market_cap > config.max_market_cap {
+        return Err(format!(
+            "MIN_MARKET_CAP ({}) must not exceed MAX_MARKET_CAP ({})",
+            config.min_market_cap, config.max_market_cap
+        ).into());
+    }
+    if config.min_holders > config.max_holders {
+        return Err(format!(
+            "MIN_HOLDERS ({}) must not exceed MAX_HOLDERS ({})",
+            config.min_holders, config.max_holders
+        ).into());
+    }
+
+    // Execution
+    if config.slippage_retry_size_factor <= 0.0 || config.slippage_retry_size_factor > 1.0 {
+        return Err(format!(
+            "SLIPPAGE_RETRY_SIZE_FACTOR must be in (0, 1] (got {})",
+            config.slippage_retry_size_factor
+        ).into());
+    }
+    if config.max_realized_slippage_bps > 10_000 {
+        return Err(format!(
+            "MAX_REALIZED_SLIPPAGE_BPS must be at most 10000 (got {})",
+            config.max_realized_slippage_bps
+        ).into());
+    }
+    if config.realized_slippage_breach_limit == 0 {
+        return Err("REALIZED_SLIPPAGE_BREACH_LIMIT must be greater than 0".into());
+    }
+    if config.confirmation_poll_interval_ms == 0 {
+        return Err("CONFIRMATION_POLL_INTERVAL_MS must be greater than 0".into());
+    }
+    if config.confirmation_poll_interval_ms > config.confirmation_timeout_ms {
+        return Err(format!(
+            "CONFIRMATION_POLL_INTERVAL_MS ({}) must not exceed CONFIRMATION_TIMEOUT_MS ({})",
+            config.confirmation_poll_interval_ms, config.confirmation_timeout_ms
+        ).into());
+    }
+
+    // Monitoring
+    if config.health_check_interval_secs == 0 {
+        return Err("HEALTH_CHECK_INTERVAL_SECS must be greater than 0".into());
+    }
+
+    // Gas Optimization
+    if let Some(url) = &config.priority_fee_api_url {
+        validate_url("PRIORITY_FEE_API_URL", url, &["http", "https"])?;
+    }
+    if config.sell_compute_unit_limit == 0 {
+        return Err("SELL_COMPUTE_UNIT_LIMIT must be greater than 0".into());
+    }
+    if config.priority_fee_lamports > config.max_priority_fee_lamports {
+        return Err(format!(
+            "PRIORITY_FEE_LAMPORTS ({}) must not exceed MAX_PRIORITY_FEE_LAMPORTS ({})",
+            config.priority_fee_lamports, config.max_priority_fee_lamports
+        ).into());
+    }
+
+    Ok(())
+}
+
+/// Check that a URL parses and uses one of the allowed schemes
+fn validate_url(name: &str, value: &str, schemes: &[&str]) -> Result<(), Box<dyn std::error::Error>> {
+    let url = reqwest::Url::parse(value)
+        .map_err(|e| format!("{} is not a valid URL ('{}'): {}", name, value, e))?;
+
+    if !schemes.contains(&url.scheme()) {
+        return Err(format!(
+            "{} must use one of the schemes {:?} (got '{}')",
+            name,
+            schemes,
+            url.scheme()
+        ).into());
+    }
+
+    Ok(())
+}
+
+/// Check that a private key is a base58-encoded 64-byte keypair
+fn validate_private_key(name: &str, value: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let bytes = bs58::decode(value)
+        .into_vec()
+        .map_err(|e| format!("{} is not valid base58: {}", name, e))?;
+
+    if bytes.len() != 64 {
+        return Err(format!("{} must decode to 64 bytes (got {})", name, bytes.len()).into());
+    }
+
+    Ok(())
+}
+
+/// Check that a percentage is greater than 0 and at most `max`
+fn validate_percentage(name: &str, value: f64, max: f64) -> Result<(), Box<dyn std::error::Error>> {
+    if !(value > 0.0 && value <= max) {
+        return Err(format!("{} must be greater than 0 and at most {} (got {})", name, max, value).into());
     }
 
     Ok(())
@@ -228,6 +574,22 @@ pub mod constants {
     // Pump.fun Fee Recipient
     pub const PUMP_FUN_FEE_RECIPIENT: Pubkey = solana_sdk::pubkey!("CebN5WGQ4jvEPvsVU4EoHEpgzq1VV7AbicfhtW4xC9iM");
 
+    // Pump.fun global config account
+    pub const PUMP_FUN_GLOBAL: Pubkey = solana_sdk::pubkey!("4wTV1YmiEkRvAtNtsSGPtUrqRYQMe5SKy2uB4Jjaxnjf");
+
+    // Offset of fee_basis_points in the global account: discriminator, initialized flag,
+    // authority, fee recipient, then four u64 curve parameters
+    pub const PUMP_FUN_GLOBAL_FEE_BPS_OFFSET: usize = 8 + 1 + 32 + 32 + 4 * 8;
+
+    // How long the protocol fee read from the global account is reused
+    pub const PUMP_FUN_FEE_CACHE_TTL_MS: u64 = 5 * ONE_MINUTE_MS;
+
+    // Anchor event discriminator for Pump.fun CreateEvent (sha256("event:CreateEvent")[..8])
+    pub const PUMP_FUN_CREATE_EVENT_DISCRIMINATOR: [u8; 8] = [27, 114, 169, 77, 222, 235, 99, 118];
+
+    // Anchor account discriminator for Pump.fun BondingCurve (sha256("account:BondingCurve")[..8])
+    pub const PUMP_FUN_BONDING_CURVE_DISCRIMINATOR: [u8; 8] = [23, 183, 248, 55, 96, 216, 172, 96];
+
     // Bonding curve seed
     pub const BONDING_CURVE_SEED: &str = "bonding-curve";
 
@@ -243,9 +605,45 @@ pub mod constants {
     pub const ONE_HOUR_MS: u64 = 60 * ONE_MINUTE_MS;
     pub const ONE_DAY_MS: u64 = 24 * ONE_HOUR_MS;
 
+    // Blockhashes expire after ~150 slots (~60s); stop using a cached one well before that
+    pub const BLOCKHASH_MAX_AGE_MS: u64 = 45 * 1000;
+
+    // Longest a blockhash can stay valid (150 slots with slow slots); confirmation
+    // gives up this long past its timeout even if the RPC still calls it valid
+    pub const BLOCKHASH_LIFETIME_MS: u64 = 90 * 1000;
+
     // Solana constants
     pub const SOL_DECIMALS: u32 = 9;
     pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+    // Fee and rent constants
+    pub const BASE_FEE_LAMPORTS: u64 = 5000;
+    pub const COMPUTE_UNIT_LIMIT: u32 = 200_000;
+    pub const TOKEN_ACCOUNT_RENT_LAMPORTS: u64 = 2_039_280;
+
+    // Accounts per getMultipleAccounts request (RPC limit)
+    pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+    // Token account close instructions packed into one transaction
+    pub const CLOSE_ACCOUNTS_PER_TX: usize = 20;
+
+    // How long to wait on the priority fee API before falling back to RPC
+    pub const PRIORITY_FEE_API_TIMEOUT_MS: u64 = 500;
+
+    // Pump.fun slippage errors (TooMuchSolRequired = 6002, TooLittleSolReceived = 6003)
+    // as they appear in program logs and transaction errors
+    pub const PUMP_FUN_SLIPPAGE_ERRORS: [&str; 4] = [
+        "TooMuchSolRequired",
+        "TooLittleSolReceived",
+        "custom program error: 0x1772",
+        "custom program error: 0x1773",
+    ];
+
+    // How long a fetched wallet balance is reused before refetching
+    pub const BALANCE_CACHE_TTL_MS: u64 = 1000;
+
+    // JSON-RPC internal error code, returned by overloaded or failing nodes
+    pub const JSON_RPC_INTERNAL_ERROR: i64 = -32603;
 }
 
 /// Transaction types for logging
@@ -271,3 +669,60 @@ pub enum TradingStatus {
     Paused,
     Stopped,
 }
+
+/// How the size of each buy is determined
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum BuySizing {
+    /// Fixed amount of SOL per buy
+    Absolute(f64),
+    /// Percentage of the wallet balance above the SOL reserve
+    PctOfBalance(f64),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_trimmed_pubkey_list_skipping_blanks() {
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        let mints = parse_pubkey_list("MINT_ALLOWLIST", &format!(" {} ,,{}, ", a, b)).unwrap();
+
+        assert_eq!(mints, vec![a, b]);
+        assert!(parse_pubkey_list("MINT_ALLOWLIST", "").unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_malformed_mint_list() {
+        let error = parse_pubkey_list("MINT_DENYLIST", &format!("{},abc", Pubkey::new_unique()))
+            .unwrap_err()
+            .to_string();
+
+        assert!(error.contains("MINT_DENYLIST"), "{}", error);
+        assert!(error.contains("'abc'"), "{}", error);
+    }
+
+    #[test]
+    fn parses_program_denylist() {
+        let programs = parse_pubkey_list(
+            "PROGRAM_DENYLIST",
+            "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4, ComputeBudget111111111111111111111111111111",
+        ).unwrap();
+
+        assert_eq!(programs, vec![
+            Pubkey::from_str("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4").unwrap(),
+            solana_sdk::compute_budget::id(),
+        ]);
+    }
+
+    #[test]
+    fn rejects_malformed_program_denylist() {
+        let error = parse_pubkey_list("PROGRAM_DENYLIST", "ComputeBudget111111111111111111111111111111,not-a-program")
+            .unwrap_err()
+            .to_string();
+
+        assert!(error.contains("PROGRAM_DENYLIST"), "{}", error);
+        assert!(error.contains("not-a-program"), "{}", error);
+    }
+}
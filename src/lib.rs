@@ -5,7 +5,9 @@ pub mod utils;
 pub mod types;
 
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::sync::RwLock;
+use tracing::Instrument;
 
 /// Main Pump.fun sniper bot structure
 pub struct PumpFunSniper {
@@ -13,6 +15,7 @@ pub struct PumpFunSniper {
     client: Arc<utils::solana_client::SolanaClient>,
     monitor: Arc<RwLock<Option<monitors::pump_fun_monitor::PumpFunMonitor>>>,
     trader: Arc<traders::trader::Trader>,
+    started_at: std::time::Instant,
 }
 
 impl PumpFunSniper {
@@ -35,6 +38,7 @@ impl PumpFunSniper {
             client,
             monitor: Arc::new(RwLock::new(None)),
             trader,
+            started_at: std::time::Instant::now(),
         })
     }
 
@@ -42,6 +46,9 @@ impl PumpFunSniper {
     pub async fn start(&self) -> Result<(), Box<dyn std::error::Error>> {
         tracing::info!("Starting Pump.fun sniper bot...");
 
+        // Pick up positions left open by a previous run
+        self.trader.recover_positions().await?;
+
         // Start the monitor
         let monitor = monitors::pump_fun_monitor::PumpFunMonitor::new(
             Arc::clone(&self.client),
@@ -51,14 +58,23 @@ impl PumpFunSniper {
         // Set up token event handler
         let trader = Arc::clone(&self.trader);
         let config = Arc::clone(&self.config);
+        let next_event_id = Arc::new(AtomicU64::new(1));
         monitor.on_new_token(move |event| {
             let trader = Arc::clone(&trader);
             let config = Arc::clone(&config);
+
+            // Every log line for this token carries the same event id
+            let span = tracing::info_span!(
+                "token_event",
+                event_id = next_event_id.fetch_add(1, Ordering::Relaxed),
+                mint = %event.token_address,
+            );
+
             tokio::spawn(async move {
                 if let Err(e) = handle_new_token(trader, config, event).await {
                     tracing::error!("Error handling new token: {}", e);
                 }
-            });
+            }.instrument(span));
         }).await;
 
         // Store the monitor
@@ -78,10 +94,71 @@ impl PumpFunSniper {
 
         self.trader.stop().await?;
 
+        // Reclaim rent from token accounts the session left empty
+        if self.config.close_empty_atas_on_shutdown && !self.config.simulation_mode {
+            match self.client.close_empty_token_accounts().await {
+                Ok(closed) => tracing::info!("Closed {} empty token accounts", closed),
+                Err(e) => tracing::error!("Failed to close empty token accounts: {}", e),
+            }
+        }
+
         tracing::info!("Pump.fun sniper bot stopped successfully");
         Ok(())
     }
 
+    /// Check component health and reconnect a monitor that has gone quiet
+    pub async fn health_check(&self) {
+        self.check_deadman_switch().await;
+
+        let timeout = std::time::Duration::from_secs(self.config.monitor_inactivity_timeout_secs);
+
+        if let Some(monitor) = self.monitor.read().await.as_ref() {
+            if monitor.is_stale(timeout).await {
+                tracing::error!(
+                    "Pump.fun monitor received nothing for over {}s, restarting connection",
+                    self.config.monitor_inactivity_timeout_secs
+                );
+                if let Err(e) = monitor.reconnect().await {
+                    tracing::error!("Failed to reconnect Pump.fun monitor: {}", e);
+                }
+            }
+        }
+    }
+
+    /// Halt trading if the operator's heartbeat file has not been touched recently
+    ///
+    /// The heartbeat is aged from process start at the earliest, so a restart
+    /// gives the operator a full interval to touch the file. While it stays
+    /// stale every check retries liquidation of whatever is still open.
+    async fn check_deadman_switch(&self) {
+        let Some(path) = &self.config.deadman_file else {
+            return;
+        };
+
+        let since_start = self.started_at.elapsed();
+        let age = std::fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .map_or(since_start, |age| age.min(since_start));
+        let interval = std::time::Duration::from_secs(self.config.deadman_interval_secs);
+        if age <= interval {
+            return;
+        }
+
+        let reason = format!(
+            "no operator heartbeat at {} within {}s",
+            path, self.config.deadman_interval_secs
+        );
+        self.trader.activate_kill_switch(&reason).await;
+
+        if self.config.deadman_liquidate {
+            if let Err(e) = self.trader.liquidate_all().await {
+                tracing::error!("Failed to liquidate positions: {}", e);
+            }
+        }
+    }
+
     /// Get bot status
     pub async fn status(&self) -> serde_json::Value {
         serde_json::json!({
@@ -89,9 +166,11 @@ impl PumpFunSniper {
                 "simulation_mode": self.config.simulation_mode,
                 "rpc_url": self.config.rpc_url,
                 "buy_amount_sol": self.config.buy_amount_sol,
+                "buy_sizing": format!("{:?}", self.config.buy_sizing),
             },
             "monitoring": {
                 "active": self.monitor.read().await.is_some(),
+                "rpc_circuit": format!("{:?}", self.trader.rpc_breaker().state()),
             },
             "trading": self.trader.status().await,
         })
@@ -110,6 +189,64 @@ async fn handle_new_token(
         event.creator
     );
 
+    // Skip denylisted mints, or mints missing from a configured allowlist
+    if !is_mint_allowed(&event.token_address, &config) {
+        tracing::info!("Token rejected by mint filters: {}", event.token_address);
+        return Ok(());
+    }
+
+    // Hold off while the RPC endpoint is failing
+    let rpc_breaker = trader.rpc_breaker();
+    if !rpc_breaker.allow() {
+        tracing::info!("Skipping token while RPC circuit breaker is open: {}", event.token_address);
+        return Ok(());
+    }
+
+    // The result is consumed before the next await so the handler future stays Send
+    let analysis = {
+        let result = analyze_new_token(&trader, &event).await;
+        rpc_breaker.record_result(&result);
+        result?
+    };
+    let Some(analysis) = analysis else {
+        return Ok(());
+    };
+
+    // Check if token passes filters
+    if !should_trade_token(&analysis, &config) {
+        tracing::info!("Token filtered out: {}", event.token_address);
+        return Ok(());
+    }
+
+    // Drop launches that went stale during validation and analysis
+    let age_ms = (chrono::Utc::now() - event.timestamp).num_milliseconds();
+    if age_ms > config.token_event_ttl_ms as i64 {
+        tracing::info!(
+            "Dropping stale token event: {} ({}ms old)",
+            event.token_address,
+            age_ms
+        );
+        return Ok(());
+    }
+
+    // Execute trade
+    trader.execute_buy(&analysis, event.timestamp).await
+}
+
+/// Validate a new token's mint and analyze it, returning `None` for invalid mints
+async fn analyze_new_token(
+    trader: &traders::trader::Trader,
+    event: &monitors::pump_fun_monitor::NewTokenEvent,
+) -> Result<Option<utils::token_analyzer::TokenAnalysis>, Box<dyn std::error::Error>> {
+    // Drop events whose decoded mint is not actually a legacy SPL mint
+    if !utils::token_analyzer::is_spl_mint(&event.token_address, trader.client()).await? {
+        tracing::info!(
+            "Dropping token event with invalid or non-legacy SPL mint: {}",
+            event.token_address
+        );
+        return Ok(None);
+    }
+
     // Analyze the token
     let analysis = utils::token_analyzer::analyze_token(
         &event.token_address,
@@ -117,15 +254,16 @@ async fn handle_new_token(
         trader.client(),
     ).await?;
 
-    // Check if token passes filters
-    if should_trade_token(&analysis, &config) {
-        // Execute trade
-        trader.execute_buy(&analysis).await?;
-    } else {
-        tracing::info!("Token filtered out: {}", event.token_address);
+    Ok(Some(analysis))
+}
+
+/// Check a mint against the configured allowlist and denylist
+fn is_mint_allowed(mint: &solana_sdk::pubkey::Pubkey, config: &config::BotConfig) -> bool {
+    if config.mint_denylist.contains(mint) {
+        return false;
     }
 
-    Ok(())
+    config.mint_allowlist.is_empty() || config.mint_allowlist.contains(mint)
 }
 
 /// Check if token should be traded based on configuration
@@ -151,3 +289,47 @@ fn should_trade_token(
 
     true
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+
+    fn config(mint_allowlist: Vec<Pubkey>, mint_denylist: Vec<Pubkey>) -> config::BotConfig {
+        config::BotConfig {
+            mint_allowlist,
+            mint_denylist,
+            ..config::BotConfig::default()
+        }
+    }
+
+    #[test]
+    fn any_mint_allowed_without_lists() {
+        assert!(is_mint_allowed(&Pubkey::new_unique(), &config(vec![], vec![])));
+    }
+
+    #[test]
+    fn allowlist_admits_only_listed_mints() {
+        let listed = Pubkey::new_unique();
+        let config = config(vec![listed], vec![]);
+
+        assert!(is_mint_allowed(&listed, &config));
+        assert!(!is_mint_allowed(&Pubkey::new_unique(), &config));
+    }
+
+    #[test]
+    fn denylist_rejects_listed_mints() {
+        let denied = Pubkey::new_unique();
+        let config = config(vec![], vec![denied]);
+
+        assert!(!is_mint_allowed(&denied, &config));
+        assert!(is_mint_allowed(&Pubkey::new_unique(), &config));
+    }
+
+    #[test]
+    fn denylist_wins_over_allowlist() {
+        let mint = Pubkey::new_unique();
+
+        assert!(!is_mint_allowed(&mint, &config(vec![mint], vec![mint])));
+    }
+}
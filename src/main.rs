@@ -10,19 +10,23 @@ mod types;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Load configuration first so it can select the log format
+    let config = Arc::new(config::load_config()?);
+
     // Initialize tracing
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "solana_pumpfun_sniper=info".into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+    let registry = tracing_subscriber::registry().with(
+        tracing_subscriber::EnvFilter::try_from_default_env()
+            .unwrap_or_else(|_| "solana_pumpfun_sniper=info".into()),
+    );
+    if config.log_json {
+        registry
+            .with(tracing_subscriber::fmt::layer().json().with_current_span(true))
+            .init();
+    } else {
+        registry.with(tracing_subscriber::fmt::layer()).init();
+    }
 
     tracing::info!("Starting Solana Pump.fun Sniper Bot (Rust Edition)");
-
-    // Load configuration
-    let config = Arc::new(config::load_config()?);
     tracing::info!("Configuration loaded successfully");
 
     // Create bot instance
@@ -43,9 +47,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     });
 
     // Health check loop
-    let mut interval = time::interval(Duration::from_secs(60));
+    let mut interval = time::interval(Duration::from_secs(config.health_check_interval_secs));
     loop {
         interval.tick().await;
+        bot.health_check().await;
         let status = bot.status().await;
         tracing::info!("Health check: {}", status);
     }
@@ -1,8 +1,12 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
 use futures_util::{SinkExt, StreamExt};
 use solana_client::rpc_config::{RpcTransactionLogsConfig, RpcTransactionLogsFilter};
-use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use std::collections::{HashSet, VecDeque};
 use std::sync::Arc;
-use tokio::sync::{mpsc, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, Mutex, RwLock};
+use tokio::task::JoinHandle;
 use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
 use serde_json::json;
 use crate::{
@@ -11,6 +15,43 @@ use crate::{
     utils::solana_client::SolanaClient,
 };
 
+/// Bounded record of recently processed transaction signatures
+///
+/// Providers can deliver the same notification twice, and a resubscribe can
+/// replay recent transactions; this keeps those from producing duplicate events.
+struct SeenSignatures {
+    order: VecDeque<String>,
+    set: HashSet<String>,
+    capacity: usize,
+}
+
+impl SeenSignatures {
+    fn new(capacity: usize) -> Self {
+        Self {
+            order: VecDeque::with_capacity(capacity),
+            set: HashSet::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Record a signature, returning false if it was already seen
+    fn insert(&mut self, signature: &str) -> bool {
+        if self.set.contains(signature) {
+            return false;
+        }
+
+        if self.order.len() >= self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.set.remove(&oldest);
+            }
+        }
+
+        self.order.push_back(signature.to_string());
+        self.set.insert(signature.to_string());
+        true
+    }
+}
+
 /// Pump.fun token launch monitor
 pub struct PumpFunMonitor {
     client: Arc<SolanaClient>,
@@ -18,6 +59,9 @@ pub struct PumpFunMonitor {
     event_sender: mpsc::UnboundedSender<NewTokenEvent>,
     event_receiver: Arc<RwLock<Option<mpsc::UnboundedReceiver<NewTokenEvent>>>>,
     is_monitoring: Arc<RwLock<bool>>,
+    last_message_at: Arc<RwLock<Option<Instant>>>,
+    seen_signatures: Arc<Mutex<SeenSignatures>>,
+    websocket_task: Arc<RwLock<Option<JoinHandle<()>>>>,
 }
 
 impl PumpFunMonitor {
@@ -27,6 +71,8 @@ impl PumpFunMonitor {
         config: Arc<BotConfig>,
     ) -> Self {
         let (event_sender, event_receiver) = mpsc::unbounded_channel();
+        // Shared across reconnects so replayed notifications are still caught
+        let seen_signatures = SeenSignatures::new(config.signature_dedup_window);
 
         Self {
             client,
@@ -34,6 +80,9 @@ impl PumpFunMonitor {
             event_sender,
             event_receiver: Arc::new(RwLock::new(Some(event_receiver))),
             is_monitoring: Arc::new(RwLock::new(false)),
+            last_message_at: Arc::new(RwLock::new(None)),
+            seen_signatures: Arc::new(Mutex::new(seen_signatures)),
+            websocket_task: Arc::new(RwLock::new(None)),
         }
     }
 
@@ -64,6 +113,11 @@ impl PumpFunMonitor {
 
         *self.is_monitoring.write().await = false;
 
+        // Stop the WebSocket reader
+        if let Some(task) = self.websocket_task.write().await.take() {
+            task.abort();
+        }
+
         // Close the event receiver
         if let Some(receiver) = self.event_receiver.write().await.take() {
             drop(receiver);
@@ -87,6 +141,28 @@ impl PumpFunMonitor {
         });
     }
 
+    /// Check whether the monitor is running but has received nothing for `timeout`
+    pub async fn is_stale(&self, timeout: Duration) -> bool {
+        if !*self.is_monitoring.read().await {
+            return false;
+        }
+
+        match *self.last_message_at.read().await {
+            Some(last_message_at) => last_message_at.elapsed() > timeout,
+            None => true,
+        }
+    }
+
+    /// Drop the current WebSocket connection and subscribe again
+    pub async fn reconnect(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(task) = self.websocket_task.write().await.take() {
+            task.abort();
+        }
+
+        tracing::info!("Reconnecting Pump.fun monitor...");
+        self.start_websocket_monitoring().await
+    }
+
     /// Start WebSocket monitoring for program logs
     async fn start_websocket_monitoring(&self) -> Result<(), Box<dyn std::error::Error>> {
         let ws_url = self.config.ws_url.as_ref()
@@ -111,20 +187,27 @@ impl PumpFunMonitor {
         });
 
         write.send(Message::Text(subscribe_message.to_string())).await?;
+        *self.last_message_at.write().await = Some(Instant::now());
 
         // Handle incoming messages
         let event_sender = self.event_sender.clone();
         let is_monitoring = Arc::clone(&self.is_monitoring);
+        let config = Arc::clone(&self.config);
+        let last_message_at = Arc::clone(&self.last_message_at);
+        let seen_signatures = Arc::clone(&self.seen_signatures);
 
-        tokio::spawn(async move {
+        let task = tokio::spawn(async move {
             while let Some(message) = read.next().await {
                 if !*is_monitoring.read().await {
                     break;
                 }
 
+                *last_message_at.write().await = Some(Instant::now());
+
                 match message {
                     Ok(Message::Text(text)) => {
-                        if let Err(e) = Self::handle_websocket_message(&text, &event_sender).await {
+                        let mut seen = seen_signatures.lock().await;
+                        if let Err(e) = Self::handle_websocket_message(&text, &event_sender, &config, &mut seen).await {
                             tracing::error!("Error handling WebSocket message: {}", e);
                         }
                     }
@@ -140,6 +223,7 @@ impl PumpFunMonitor {
                 }
             }
         });
+        *self.websocket_task.write().await = Some(task);
 
         Ok(())
     }
@@ -148,13 +232,31 @@ impl PumpFunMonitor {
     async fn handle_websocket_message(
         text: &str,
         event_sender: &mpsc::UnboundedSender<NewTokenEvent>,
+        config: &BotConfig,
+        seen: &mut SeenSignatures,
     ) -> Result<(), Box<dyn std::error::Error>> {
         let message: serde_json::Value = serde_json::from_str(text)?;
 
         // Check if this is a logs notification
         if let Some(params) = message.get("params") {
             if let Some(result) = params.get("result") {
+                let signature = result
+                    .get("value")
+                    .and_then(|v| v.get("signature"))
+                    .and_then(|s| s.as_str());
+                if let Some(signature) = signature {
+                    if !seen.insert(signature) {
+                        tracing::debug!("Skipping duplicate notification for {}", signature);
+                        return Ok(());
+                    }
+                }
+
                 if let Some(logs) = Self::extract_logs_from_notification(result) {
+                    if Self::invokes_denylisted_program(logs, &config.program_denylist) {
+                        tracing::debug!("Skipping transaction invoking a denylisted program");
+                        return Ok(());
+                    }
+
                     if let Some(token_event) = Self::parse_token_creation(logs).await {
                         if event_sender.send(token_event).is_err() {
                             tracing::error!("Failed to send token event - channel closed");
@@ -167,43 +269,188 @@ impl PumpFunMonitor {
         Ok(())
     }
 
-    /// Extract logs from notification
+    /// Extract logs from notification, skipping failed transactions
     fn extract_logs_from_notification(result: &serde_json::Value) -> Option<&serde_json::Value> {
-        result.get("value").and_then(|v| v.get("logs"))
+        let value = result.get("value")?;
+        if value.get("err").is_some_and(|err| !err.is_null()) {
+            return None;
+        }
+        value.get("logs")
+    }
+
+    /// Check whether any `Program <id> invoke` log line names a denylisted program
+    fn invokes_denylisted_program(logs: &serde_json::Value, denylist: &[Pubkey]) -> bool {
+        if denylist.is_empty() {
+            return false;
+        }
+
+        logs.as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|log| log.as_str()?.strip_prefix("Program ")?.split_whitespace().next())
+            .filter_map(|program_id| program_id.parse::<Pubkey>().ok())
+            .any(|program_id| denylist.contains(&program_id))
     }
 
     /// Parse token creation from transaction logs
+    ///
+    /// Pump.fun emits a `CreateEvent` as a `Program data:` log line when a
+    /// token is created, which carries the mint, bonding curve and creator.
     async fn parse_token_creation(logs: &serde_json::Value) -> Option<NewTokenEvent> {
-        if let Some(logs_array) = logs.as_array() {
-            // Look for Pump.fun specific log patterns
-            let has_create_log = logs_array.iter().any(|log| {
-                log.as_str()
-                    .map(|s| s.contains("Create") || s.contains("create"))
-                    .unwrap_or(false)
-            });
-
-            if has_create_log {
-                // In a real implementation, you'd parse the transaction to get token details
-                // For now, return a placeholder event
-                Some(NewTokenEvent {
-                    token_address: solana_sdk::pubkey::Pubkey::new_unique(),
-                    bonding_curve_address: solana_sdk::pubkey::Pubkey::new_unique(),
-                    creator: solana_sdk::pubkey::Pubkey::new_unique(),
-                    timestamp: chrono::Utc::now(),
-                })
-            } else {
-                None
-            }
-        } else {
-            None
+        logs.as_array()?
+            .iter()
+            .filter_map(|log| log.as_str()?.strip_prefix("Program data: "))
+            .filter_map(|data| BASE64.decode(data).ok())
+            .find_map(|data| Self::decode_create_event(&data))
+    }
+
+    /// Decode a borsh-serialized Pump.fun `CreateEvent`
+    ///
+    /// Layout: discriminator, name, symbol, uri (u32-prefixed strings),
+    /// then mint, bonding curve and creator pubkeys.
+    fn decode_create_event(data: &[u8]) -> Option<NewTokenEvent> {
+        let payload = data.strip_prefix(PUMP_FUN_CREATE_EVENT_DISCRIMINATOR.as_slice())?;
+
+        // Skip name, symbol and uri
+        let mut offset = 0;
+        for _ in 0..3 {
+            let len = u32::from_le_bytes(payload.get(offset..offset + 4)?.try_into().ok()?);
+            offset = offset.checked_add(4 + len as usize)?;
         }
+
+        let mut read_pubkey = || {
+            let key = Pubkey::try_from(payload.get(offset..offset + 32)?).ok()?;
+            offset += 32;
+            Some(key)
+        };
+
+        Some(NewTokenEvent {
+            token_address: read_pubkey()?,
+            bonding_curve_address: read_pubkey()?,
+            creator: read_pubkey()?,
+            timestamp: chrono::Utc::now(),
+        })
     }
 
     /// Get monitor status
     pub async fn status(&self) -> serde_json::Value {
         json!({
             "is_monitoring": *self.is_monitoring.read().await,
+            "last_message_secs_ago": self.last_message_at.read().await.map(|t| t.elapsed().as_secs()),
             "program_id": PUMP_FUN_PROGRAM_ID.to_string(),
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A `CreateEvent` laid out the way the program logs it
+    fn create_event_payload(mint: &Pubkey, bonding_curve: &Pubkey, creator: &Pubkey) -> Vec<u8> {
+        let mut data = PUMP_FUN_CREATE_EVENT_DISCRIMINATOR.to_vec();
+        for field in ["Test Token", "TEST", "https://example.com/meta.json"] {
+            data.extend_from_slice(&(field.len() as u32).to_le_bytes());
+            data.extend_from_slice(field.as_bytes());
+        }
+        data.extend_from_slice(mint.as_ref());
+        data.extend_from_slice(bonding_curve.as_ref());
+        data.extend_from_slice(creator.as_ref());
+        data
+    }
+
+    #[test]
+    fn decodes_create_event() {
+        let (mint, bonding_curve, creator) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+
+        let event = PumpFunMonitor::decode_create_event(&create_event_payload(&mint, &bonding_curve, &creator))
+            .expect("payload should decode");
+
+        assert_eq!(event.token_address, mint);
+        assert_eq!(event.bonding_curve_address, bonding_curve);
+        assert_eq!(event.creator, creator);
+    }
+
+    #[test]
+    fn rejects_wrong_discriminator() {
+        let mut data = create_event_payload(&Pubkey::new_unique(), &Pubkey::new_unique(), &Pubkey::new_unique());
+        data[0] ^= 0xff;
+
+        assert!(PumpFunMonitor::decode_create_event(&data).is_none());
+    }
+
+    #[test]
+    fn rejects_truncated_payload() {
+        let data = create_event_payload(&Pubkey::new_unique(), &Pubkey::new_unique(), &Pubkey::new_unique());
+
+        assert!(PumpFunMonitor::decode_create_event(&data[..data.len() - 1]).is_none());
+        assert!(PumpFunMonitor::decode_create_event(&data[..12]).is_none());
+    }
+
+    #[test]
+    fn rejects_oversized_string_length() {
+        let mut data = create_event_payload(&Pubkey::new_unique(), &Pubkey::new_unique(), &Pubkey::new_unique());
+        data[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        assert!(PumpFunMonitor::decode_create_event(&data).is_none());
+    }
+
+    #[test]
+    fn detects_denylisted_program_invocation() {
+        let denied = Pubkey::new_unique();
+        let logs = json!([
+            format!("Program {} invoke [1]", PUMP_FUN_PROGRAM_ID),
+            format!("Program {} invoke [2]", denied),
+            format!("Program {} success", denied),
+        ]);
+
+        assert!(PumpFunMonitor::invokes_denylisted_program(&logs, &[denied]));
+    }
+
+    #[test]
+    fn ignores_programs_not_on_the_denylist() {
+        let denied = Pubkey::new_unique();
+        let logs = json!([
+            format!("Program {} invoke [1]", PUMP_FUN_PROGRAM_ID),
+            format!("Program log: transfer to {}", denied),
+            "Program data: vdt/007mYe4=",
+        ]);
+
+        assert!(!PumpFunMonitor::invokes_denylisted_program(&logs, &[denied]));
+        assert!(!PumpFunMonitor::invokes_denylisted_program(&logs, &[]));
+    }
+
+    #[test]
+    fn tolerates_malformed_logs() {
+        let denied = Pubkey::new_unique();
+
+        assert!(!PumpFunMonitor::invokes_denylisted_program(&json!(null), &[denied]));
+        let logs = json!([42, "Program", "Program not-a-key invoke [1]"]);
+        assert!(!PumpFunMonitor::invokes_denylisted_program(&logs, &[denied]));
+    }
+
+    #[test]
+    fn seen_signatures_reject_duplicates() {
+        let mut seen = SeenSignatures::new(4);
+
+        assert!(seen.insert("sig-a"));
+        assert!(!seen.insert("sig-a"));
+        assert!(seen.insert("sig-b"));
+        assert!(!seen.insert("sig-b"));
+    }
+
+    #[test]
+    fn seen_signatures_evict_oldest_at_capacity() {
+        let mut seen = SeenSignatures::new(2);
+
+        assert!(seen.insert("sig-a"));
+        assert!(seen.insert("sig-b"));
+        assert!(seen.insert("sig-c"));
+
+        assert_eq!(seen.order.len(), 2);
+        assert_eq!(seen.set.len(), 2);
+        assert!(!seen.insert("sig-b"));
+        assert!(!seen.insert("sig-c"));
+        assert!(seen.insert("sig-a"));
+    }
+}
@@ -1 +1,2 @@
 pub mod trader;
+pub mod position_store;
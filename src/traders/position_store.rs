@@ -0,0 +1,46 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use crate::types::{Position, PositionStatus};
+
+/// File-backed store of open positions, so they survive a restart
+pub struct PositionStore {
+    path: PathBuf,
+}
+
+impl PositionStore {
+    /// Create a store backed by the JSON file at `path`
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Load persisted positions, or none if the file does not exist yet
+    pub fn load(&self) -> Result<Vec<Position>, Box<dyn std::error::Error>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let contents = std::fs::read_to_string(&self.path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    /// Persist every position that is not closed
+    ///
+    /// Writes to a temporary file and renames it over the store, so a crash
+    /// mid-write never leaves a truncated file behind.
+    pub fn save(&self, positions: &HashMap<String, Position>) -> Result<(), Box<dyn std::error::Error>> {
+        let open: Vec<&Position> = positions
+            .values()
+            .filter(|position| position.status != PositionStatus::Closed)
+            .collect();
+
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        let tmp_path = self.path.with_extension("tmp");
+        std::fs::write(&tmp_path, serde_json::to_string_pretty(&open)?)?;
+        std::fs::rename(&tmp_path, &self.path)?;
+
+        Ok(())
+    }
+}
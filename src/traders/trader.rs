@@ -1,11 +1,20 @@
 use std::sync::Arc;
-use std::collections::HashMap;
-use tokio::sync::RwLock;
-use chrono::Utc;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, OwnedMutexGuard, RwLock, Semaphore};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use solana_sdk::pubkey::Pubkey;
+use super::position_store::PositionStore;
 use crate::{
-    config::BotConfig,
-    types::{TokenAnalysis, TradeResult, TradeType, Position, PositionStatus},
-    utils::{solana_client::SolanaClient, transaction_builder::TransactionBuilder},
+    config::{BotConfig, BuySizing, TradingStatus, constants::*},
+    types::{BondingCurveInfo, TokenAnalysis, TradeResult, TradeType, Position, PositionStatus, SellReason},
+    utils::{
+        circuit_breaker::RpcCircuitBreaker,
+        slippage_model::SlippageModel,
+        solana_client::SolanaClient,
+        transaction_builder::{self, TransactionBuilder},
+    },
 };
 
 /// Trading bot for executing buy/sell orders
@@ -13,12 +22,59 @@ pub struct Trader {
     client: Arc<SolanaClient>,
     config: Arc<BotConfig>,
     transaction_builder: Arc<TransactionBuilder>,
+    slippage_model: SlippageModel,
     positions: Arc<RwLock<HashMap<String, Position>>>,
+    position_store: Option<PositionStore>,
+    trade_permits: Arc<Semaphore>,
+    rpc_breaker: Arc<RpcCircuitBreaker>,
+    mint_locks: Arc<DashMap<Pubkey, Arc<Mutex<()>>>>,
+    balance_cache: Arc<RwLock<Option<(f64, Instant)>>>,
+    pump_fee_cache: Arc<RwLock<Option<(u64, Instant)>>>,
     is_buying: Arc<RwLock<bool>>,
-    is_selling: Arc<RwLock<bool>>,
     last_buy_time: Arc<RwLock<u64>>,
     daily_trades: Arc<RwLock<u32>>,
+    recent_buys: Arc<RwLock<VecDeque<Instant>>>,
     last_reset_date: Arc<RwLock<String>>,
+    realized_pnl: Arc<RwLock<f64>>,
+    latency_budget_exceeded: Arc<RwLock<u64>>,
+    realized_slippage: Arc<RwLock<RealizedSlippage>>,
+    partial_fills: Arc<RwLock<u64>>,
+    trading_status: Arc<RwLock<TradingStatus>>,
+}
+
+/// Realized slippage of recent confirmed buys
+#[derive(Default)]
+struct RealizedSlippage {
+    last_bps: Option<u64>,
+    consecutive_breaches: u32,
+}
+
+/// How a confirmed buy filled against the expected token amount
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FillStatus {
+    /// At least `min_fill_ratio` of the expected tokens arrived
+    Full,
+    /// Some tokens arrived, but fewer than `min_fill_ratio` of the expected amount
+    Partial,
+    /// No tokens arrived
+    Empty,
+}
+
+/// Held lock on a single mint's trades
+///
+/// Dropping it releases the lock and removes the mint's entry once no other
+/// trade holds or waits on it, so the lock map doesn't grow with every launch.
+struct MintGuard {
+    guard: Option<OwnedMutexGuard<()>>,
+    mint: Pubkey,
+    mint_locks: Arc<DashMap<Pubkey, Arc<Mutex<()>>>>,
+}
+
+impl Drop for MintGuard {
+    fn drop(&mut self) {
+        drop(self.guard.take());
+        self.mint_locks.remove_if(&self.mint, |_, lock| Arc::strong_count(lock) == 1);
+    }
 }
 
 impl Trader {
@@ -32,16 +88,33 @@ impl Trader {
             Arc::clone(&config),
         ));
 
+        let slippage_model = SlippageModel::from_config(&config);
+        let trade_permits = Arc::new(Semaphore::new(config.max_concurrent_trades));
+        let rpc_breaker = Arc::new(RpcCircuitBreaker::new(&config));
+        let position_store = config.position_store_path.as_ref().map(PositionStore::new);
+
         Ok(Self {
             client,
             config,
             transaction_builder,
+            slippage_model,
             positions: Arc::new(RwLock::new(HashMap::new())),
+            position_store,
+            trade_permits,
+            rpc_breaker,
+            mint_locks: Arc::new(DashMap::new()),
+            balance_cache: Arc::new(RwLock::new(None)),
+            pump_fee_cache: Arc::new(RwLock::new(None)),
             is_buying: Arc::new(RwLock::new(false)),
-            is_selling: Arc::new(RwLock::new(false)),
             last_buy_time: Arc::new(RwLock::new(0)),
             daily_trades: Arc::new(RwLock::new(0)),
+            recent_buys: Arc::new(RwLock::new(VecDeque::new())),
             last_reset_date: Arc::new(RwLock::new(Utc::now().format("%Y-%m-%d").to_string())),
+            realized_pnl: Arc::new(RwLock::new(0.0)),
+            latency_budget_exceeded: Arc::new(RwLock::new(0)),
+            realized_slippage: Arc::new(RwLock::new(RealizedSlippage::default())),
+            partial_fills: Arc::new(RwLock::new(0)),
+            trading_status: Arc::new(RwLock::new(TradingStatus::Active)),
         })
     }
 
@@ -50,83 +123,329 @@ impl Trader {
         &self.client
     }
 
-    /// Execute a buy order
-    pub async fn execute_buy(&self, analysis: &TokenAnalysis) -> Result<(), Box<dyn std::error::Error>> {
+    /// Get the circuit breaker guarding RPC-dependent work
+    pub fn rpc_breaker(&self) -> &Arc<RpcCircuitBreaker> {
+        &self.rpc_breaker
+    }
+
+    /// Execute a buy order for a token detected at `detected_at`
+    pub async fn execute_buy(
+        &self,
+        analysis: &TokenAnalysis,
+        detected_at: DateTime<Utc>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
         // Check if buying is allowed
         if !self.can_buy().await {
             tracing::warn!("Buy blocked by safety limits");
             return Ok(());
         }
 
+        let mint = analysis.token.address.to_string();
+        let had_position = self.positions.read().await.contains_key(&mint);
+
+        // Serialize with any other trade on this mint, then wait for a free execution slot
+        let _mint_guard = self.lock_mint(&analysis.token.address).await;
+        let _permit = self.trade_permits.acquire().await?;
+
+        // A concurrent buy of this mint may have gone through while we waited
+        if !had_position && self.positions.read().await.contains_key(&mint) {
+            tracing::info!("Skipping buy for {}: a concurrent buy already opened a position", analysis.token.symbol);
+            return Ok(());
+        }
+
+        // Limits, cooldown and the kill switch may have changed while we waited;
+        // the re-check also claims this buy's slot in the per-minute window
+        let Some(slot) = self.reserve_buy_slot().await else {
+            tracing::warn!("Buy blocked by safety limits");
+            return Ok(());
+        };
+
+        // Hold off while the RPC endpoint is failing
+        if !self.rpc_breaker.allow() {
+            tracing::info!("Skipping buy for {} while RPC circuit breaker is open", analysis.token.symbol);
+            self.release_buy_slot(slot).await;
+            return Ok(());
+        }
+
+        // Settle the breaker and drop the error before awaiting again, so the
+        // future stays Send for the spawned event handler
+        *self.is_buying.write().await = true;
+        let bought = {
+            let result = self.buy_with_reserved_slot(analysis, detected_at, &mint).await;
+            self.rpc_breaker.record_result(&result);
+            result.map_err(|e| e.to_string())
+        };
+        *self.is_buying.write().await = false;
+        if !matches!(bought, Ok(true)) {
+            self.release_buy_slot(slot).await;
+        }
+        bought.map(|_| ()).map_err(Into::into)
+    }
+
+    /// Run a buy whose rate-limit slot is already claimed
+    ///
+    /// Returns whether a buy went through, so the caller can give the slot
+    /// back otherwise.
+    async fn buy_with_reserved_slot(
+        &self,
+        analysis: &TokenAnalysis,
+        detected_at: DateTime<Utc>,
+        mint: &str,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
         // Check simulation mode
         if self.config.simulation_mode {
-            return self.simulate_buy(analysis).await;
+            return self.simulate_buy(analysis).await.map(|_| true);
         }
 
-        // Check balance
-        let balance = self.client.get_wallet_balance().await?;
-        if balance < self.config.buy_amount_sol + 0.01 {
-            tracing::warn!("Insufficient balance for buy: {} SOL", balance);
-            return Ok(());
+        // Size the buy so fees, account rent and the SOL reserve stay covered
+        let balance = self.cached_wallet_balance().await?;
+        let target_sol = self.target_buy_sol(balance);
+        let mut amount_sol = self.buy_size_sol(analysis, balance, target_sol).await;
+        if amount_sol <= 0.0 {
+            tracing::warn!(
+                "Insufficient balance for buy: {} SOL ({} SOL reserved)",
+                balance,
+                self.config.min_sol_reserve
+            );
+            return Ok(false);
+        }
+        if amount_sol < target_sol {
+            tracing::info!(
+                "Downsizing buy from {} SOL to {} SOL to keep the {} SOL reserve",
+                target_sol,
+                amount_sol,
+                self.config.min_sol_reserve
+            );
+        }
+
+        // Keep the buy's own price impact on the curve within bounds
+        if price_impact_percentage(&analysis.bonding_curve, amount_sol) > self.config.max_price_impact_percentage {
+            let capped_sol = analysis.bonding_curve.virtual_sol_reserves as f64 / LAMPORTS_PER_SOL as f64
+                * self.config.max_price_impact_percentage / 100.0;
+            if capped_sol <= 0.0 {
+                tracing::warn!("Buy blocked: no size fits within the price impact cap");
+                return Ok(false);
+            }
+            tracing::info!(
+                "Downsizing buy from {} SOL to {} SOL to stay within {}% price impact",
+                amount_sol,
+                capped_sol,
+                self.config.max_price_impact_percentage
+            );
+            amount_sol = capped_sol;
+        }
+
+        // Cap cumulative exposure to a single mint
+        if let Some(max_sol_per_mint) = self.config.max_sol_per_mint {
+            let exposure = self.mint_exposure_sol(mint).await;
+            if exposure + amount_sol > max_sol_per_mint {
+                tracing::warn!(
+                    "Buy blocked: {} SOL into {} would exceed the per-mint cap of {} SOL ({} SOL held)",
+                    amount_sol,
+                    analysis.token.symbol,
+                    max_sol_per_mint,
+                    exposure
+                );
+                return Ok(false);
+            }
         }
 
         tracing::info!(
             "Executing buy for {}: {} SOL",
             analysis.token.symbol,
-            self.config.buy_amount_sol
+            amount_sol
         );
 
-        *self.is_buying.write().await = true;
-
-        // Build transaction
-        let transaction = self.transaction_builder.build_buy_transaction(
-            &analysis.token.address,
-            &analysis.bonding_curve.address,
-            self.config.buy_amount_sol,
-            self.config.max_slippage,
-        ).await?;
+        // Record what we already hold so the fill can be measured afterwards
+        let balance_before = if self.positions.read().await.contains_key(mint) {
+            self.client.get_token_balance(&analysis.token.address).await?
+        } else {
+            0
+        };
 
-        // Send transaction
-        match self.client.send_transaction(transaction).await {
-            Ok(signature) => {
-                // Update tracking
-                self.update_buy_tracking().await;
+        let mut retried = false;
+        let result = loop {
+            // Scale slippage with how large the buy is relative to the pool
+            let slippage = self.slippage_model.slippage_for(
+                amount_sol,
+                analysis.metrics.liquidity,
+            );
 
-                // Create position
-                self.create_position(analysis, signature).await;
+            // Build transaction
+            let fee_bps = self.pump_fee_bps().await?;
+            let transaction = self.transaction_builder.build_buy_transaction(
+                &analysis.token.address,
+                &analysis.bonding_curve.address,
+                amount_sol,
+                fee_bps,
+                slippage,
+            ).await?;
 
-                tracing::info!(
-                    "Buy executed successfully: {} - {}",
+            // Don't submit a buy the pipeline took too long to get ready
+            let elapsed_ms = (Utc::now() - detected_at).num_milliseconds();
+            if elapsed_ms > self.config.max_latency_budget_ms as i64 {
+                *self.latency_budget_exceeded.write().await += 1;
+                tracing::warn!(
+                    "Aborting buy for {}: {}ms since detection exceeds the {}ms latency budget",
                     analysis.token.symbol,
-                    signature
+                    elapsed_ms,
+                    self.config.max_latency_budget_ms
                 );
+                return Ok(false);
+            }
 
-                Ok(())
+            // Send transaction, re-simulating large buys against current state first
+            let resimulate = self.config.resimulate_before_send
+                && amount_sol >= self.config.resimulate_min_sol;
+            match self.client.send_transaction(transaction, resimulate).await {
+                // The curve moved past our limit; try once more with a smaller buy
+                Err(e) if self.config.retry_on_slippage && !retried && is_slippage_error(&e.to_string()) => {
+                    retried = true;
+                    amount_sol *= self.config.slippage_retry_size_factor;
+                    tracing::warn!(
+                        "Buy for {} exceeded slippage, retrying with {} SOL",
+                        analysis.token.symbol,
+                        amount_sol
+                    );
+                }
+                result => break result,
             }
+        };
+
+        // Send and confirmation failures go up so the RPC breaker can see them
+        let signature = result?;
+
+        // Update tracking
+        self.update_buy_tracking().await;
+        *self.balance_cache.write().await = None;
+
+        // The buy landed, so it has to end up tracked even if the lookups below fail
+        let expected = match self.expected_token_amount(analysis, amount_sol).await {
+            Ok(expected) => expected,
             Err(e) => {
-                tracing::error!("Buy execution failed: {}", e);
-                Ok(())
+                tracing::warn!("Failed to net buy {} of fees, using the quoted amount: {}", signature, e);
+                quoted_token_amount(analysis, amount_sol)
             }
-        }
+        };
+        let received = match self.client.get_token_balance(&analysis.token.address).await {
+            Ok(balance) => Some(balance.saturating_sub(balance_before)),
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to measure the fill of buy {}, recording the expected {} tokens: {}",
+                    signature,
+                    expected,
+                    e
+                );
+                None
+            }
+        };
+
+        // A confirmed buy can still deliver next to nothing
+        let (amount, entry_price) = match received {
+            Some(received) => match classify_fill(expected, received, self.config.min_fill_ratio) {
+                FillStatus::Empty => {
+                    tracing::error!(
+                        "Buy {} for {} confirmed but received no tokens (expected ~{})",
+                        signature,
+                        analysis.token.symbol,
+                        expected
+                    );
+
+                    // Nothing to hold, so the spent SOL is a realized loss
+                    *self.realized_pnl.write().await -= amount_sol;
+                    self.check_drawdown().await;
+                    return Ok(true);
+                }
+                FillStatus::Partial => {
+                    tracing::error!(
+                        "Partial fill on buy {} for {}: received {} tokens (expected ~{})",
+                        signature,
+                        analysis.token.symbol,
+                        received,
+                        expected
+                    );
+                    *self.partial_fills.write().await += 1;
+                    self.record_realized_slippage(analysis, expected, received).await;
+
+                    // The spent SOL bought fewer tokens, so price the position at what it cost
+                    (received, amount_sol / received as f64)
+                }
+                FillStatus::Full => {
+                    self.record_realized_slippage(analysis, expected, received).await;
+                    (received, analysis.metrics.price)
+                }
+            },
+            None => (expected, analysis.metrics.price),
+        };
+
+        // Create position
+        self.create_position(analysis, signature.clone(), amount, entry_price).await;
+
+        tracing::info!(
+            "Buy executed successfully: {} - {}",
+            analysis.token.symbol,
+            signature
+        );
+
+        Ok(true)
     }
 
     /// Execute a sell order
+    ///
+    /// Take-profit sells wait out the minimum hold time; stop-loss and
+    /// liquidation sells always go through.
     pub async fn execute_sell(
         &self,
         position: &Position,
         percentage: f64,
+        reason: SellReason,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        if *self.is_selling.read().await {
-            tracing::warn!("Sell already in progress");
+        if reason == SellReason::TakeProfit && !self.held_min_time(position) {
+            tracing::debug!("Deferring take profit on {} until minimum hold time", position.token_symbol);
             return Ok(());
         }
 
+        // Hold off while the RPC endpoint is failing
+        if !self.rpc_breaker.allow() {
+            tracing::warn!("Deferring sell of {} while RPC circuit breaker is open", position.token_symbol);
+            return Ok(());
+        }
+
+        let result = self.sell_position(position, percentage).await;
+        self.rpc_breaker.record_result(&result);
+        result
+    }
+
+    /// Sell `percentage` of a position once the trade has been cleared to run
+    async fn sell_position(
+        &self,
+        position: &Position,
+        percentage: f64,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // Serialize with any other trade on this mint, then wait for a free execution slot
+        let _mint_guard = self.lock_mint(&position.token_address).await;
+        let _permit = self.trade_permits.acquire().await?;
+
         if self.config.simulation_mode {
             return self.simulate_sell(position, percentage).await;
         }
 
-        let amount_to_sell = ((position.amount as f64) * percentage / 100.0) as u64;
-        let estimated_value = (amount_to_sell as f64) * position.current_price;
+        // Size the sell from what the wallet actually holds, not the tracked estimate
+        let held = self.client.get_token_balance(&position.token_address).await?;
+        if held == 0 {
+            tracing::warn!("No {} tokens held, skipping sell", position.token_symbol);
+            return Ok(());
+        }
+
+        let amount_to_sell = if percentage >= 100.0 {
+            held
+        } else {
+            ((held as f64) * percentage / 100.0) as u64
+        };
+        // The protocol fee comes out of the SOL the curve pays us
+        let fee_bps = self.pump_fee_bps().await?;
+        let estimated_value = (amount_to_sell as f64) * position.current_price
+            * (1.0 - fee_bps as f64 / 10_000.0);
         let min_sol_output = ((estimated_value * (1.0 - self.config.max_slippage / 100.0)) * 1_000_000_000.0) as u64;
 
         tracing::info!(
@@ -136,21 +455,24 @@ impl Trader {
             amount_to_sell
         );
 
-        *self.is_selling.write().await = true;
-
         // Build transaction
         let transaction = self.transaction_builder.build_sell_transaction(
             &position.token_address,
-            &solana_sdk::pubkey::Pubkey::new_unique(), // Would need actual bonding curve
+            &transaction_builder::bonding_curve_address(&position.token_address),
             amount_to_sell,
             min_sol_output,
         ).await?;
 
-        // Send transaction
-        match self.client.send_transaction(transaction).await {
+        // Send transaction, re-simulating large sells against current state first
+        let resimulate = self.config.resimulate_before_send
+            && estimated_value >= self.config.resimulate_min_sol;
+        let result = self.client.send_transaction(transaction, resimulate).await;
+
+        match result {
             Ok(signature) => {
                 // Update position
                 self.update_position_after_sell(position, amount_to_sell).await;
+                *self.balance_cache.write().await = None;
 
                 tracing::info!(
                     "Sell executed successfully: {} - {}",
@@ -160,10 +482,8 @@ impl Trader {
 
                 Ok(())
             }
-            Err(e) => {
-                tracing::error!("Sell execution failed: {}", e);
-                Ok(())
-            }
+            // Send and confirmation failures go up so the RPC breaker can see them
+            Err(e) => Err(e),
         }
     }
 
@@ -176,12 +496,18 @@ impl Trader {
             self.update_position_price(&position).await?;
 
             // Check take profit
-            if self.should_take_profit(&position) {
-                self.execute_sell(&position, 100.0).await?;
+            let result = if self.should_take_profit(&position) {
+                self.execute_sell(&position, 100.0, SellReason::TakeProfit).await
             }
             // Check stop loss
             else if self.should_stop_loss(&position) {
-                self.execute_sell(&position, 100.0).await?;
+                self.execute_sell(&position, 100.0, SellReason::StopLoss).await
+            } else {
+                Ok(())
+            };
+
+            if let Err(e) = result {
+                tracing::error!("Sell execution failed for {}: {}", position.token_symbol, e);
             }
         }
 
@@ -197,7 +523,13 @@ impl Trader {
         );
 
         self.update_buy_tracking().await;
-        self.create_position(analysis, "sim_".to_string() + &Utc::now().timestamp().to_string()).await;
+        let amount = self.expected_token_amount(analysis, self.config.buy_amount_sol).await?;
+        self.create_position(
+            analysis,
+            "sim_".to_string() + &Utc::now().timestamp().to_string(),
+            amount,
+            analysis.metrics.price,
+        ).await;
 
         Ok(())
     }
@@ -218,6 +550,40 @@ impl Trader {
 
     /// Check if buying is allowed
     async fn can_buy(&self) -> bool {
+        let mut recent_buys = self.recent_buys.write().await;
+        self.check_buy_limits(&mut recent_buys).await
+    }
+
+    /// Check if buying is allowed and, if so, claim a slot in the per-minute window
+    ///
+    /// The window stays locked from the check to the claim, so concurrent buys
+    /// can't all pass before any of them is counted.
+    async fn reserve_buy_slot(&self) -> Option<Instant> {
+        let mut recent_buys = self.recent_buys.write().await;
+        if !self.check_buy_limits(&mut recent_buys).await {
+            return None;
+        }
+
+        let slot = Instant::now();
+        recent_buys.push_back(slot);
+        Some(slot)
+    }
+
+    /// Give back the window slot of a buy that did not go through
+    async fn release_buy_slot(&self, slot: Instant) {
+        let mut recent_buys = self.recent_buys.write().await;
+        if let Some(index) = recent_buys.iter().position(|t| *t == slot) {
+            recent_buys.remove(index);
+        }
+    }
+
+    /// Check the kill switch, cooldown and trade limits against the per-minute window
+    async fn check_buy_limits(&self, recent_buys: &mut VecDeque<Instant>) -> bool {
+        // Check kill switch
+        if *self.trading_status.read().await != TradingStatus::Active {
+            return false;
+        }
+
         // Check cooldown
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -235,14 +601,94 @@ impl Trader {
             return false;
         }
 
-        // Check if another buy is in progress
-        if *self.is_buying.read().await {
+        // Check per-minute rate limit over a sliding window
+        while recent_buys
+            .front()
+            .is_some_and(|t| t.elapsed() >= Duration::from_millis(ONE_MINUTE_MS))
+        {
+            recent_buys.pop_front();
+        }
+        if recent_buys.len() >= self.config.max_trades_per_minute as usize {
+            tracing::warn!(
+                "Buy rate limited: {} buys in the last minute",
+                recent_buys.len()
+            );
             return false;
         }
 
         true
     }
 
+    /// Acquire the lock that serializes trades on a single mint
+    async fn lock_mint(&self, mint: &Pubkey) -> MintGuard {
+        let lock = Arc::clone(self.mint_locks.entry(*mint).or_default().value());
+        MintGuard {
+            guard: Some(lock.lock_owned().await),
+            mint: *mint,
+            mint_locks: Arc::clone(&self.mint_locks),
+        }
+    }
+
+    /// Get the Pump.fun protocol fee, reusing a recent reading
+    async fn pump_fee_bps(&self) -> Result<u64, Box<dyn std::error::Error>> {
+        if let Some((fee_bps, fetched_at)) = *self.pump_fee_cache.read().await {
+            if fetched_at.elapsed() < Duration::from_millis(PUMP_FUN_FEE_CACHE_TTL_MS) {
+                return Ok(fee_bps);
+            }
+        }
+
+        let fee_bps = self.client.get_pump_fee_basis_points().await?;
+        *self.pump_fee_cache.write().await = Some((fee_bps, Instant::now()));
+        Ok(fee_bps)
+    }
+
+    /// Get the wallet balance, reusing a recent reading
+    async fn cached_wallet_balance(&self) -> Result<f64, Box<dyn std::error::Error>> {
+        if let Some((balance, fetched_at)) = *self.balance_cache.read().await {
+            if fetched_at.elapsed() < Duration::from_millis(BALANCE_CACHE_TTL_MS) {
+                return Ok(balance);
+            }
+        }
+
+        let balance = self.client.get_wallet_balance().await?;
+        *self.balance_cache.write().await = Some((balance, Instant::now()));
+        Ok(balance)
+    }
+
+    /// Buy size the configured sizing asks for at the given balance
+    fn target_buy_sol(&self, balance: f64) -> f64 {
+        match self.config.buy_sizing {
+            BuySizing::Absolute(amount_sol) => amount_sol,
+            BuySizing::PctOfBalance(percentage) => {
+                ((balance - self.config.min_sol_reserve) * percentage / 100.0).max(0.0)
+            }
+        }
+    }
+
+    /// Largest buy up to `target_sol` that leaves the SOL reserve untouched
+    ///
+    /// Returns 0 when the balance cannot cover fees and rent above the reserve.
+    async fn buy_size_sol(&self, analysis: &TokenAnalysis, balance: f64, target_sol: f64) -> f64 {
+        let available = balance - self.config.min_sol_reserve - self.buy_overhead_sol(analysis).await;
+        let max_cost_factor = 1.0 + self.config.max_slippage / 100.0;
+
+        (available / max_cost_factor).clamp(0.0, target_sol)
+    }
+
+    /// Estimate the SOL a buy consumes beyond the swap itself: fees plus a rent allowance
+    async fn buy_overhead_sol(&self, analysis: &TokenAnalysis) -> f64 {
+        let priority_fee = self.config.max_priority_fee_lamports * COMPUTE_UNIT_LIMIT as u64 / 1_000_000;
+        let mut fee_lamports = BASE_FEE_LAMPORTS + priority_fee;
+
+        // Set aside rent on the first buy of a mint in case our token account has
+        // to be created; the buy transaction itself does not create it
+        if !self.positions.read().await.contains_key(&analysis.token.address.to_string()) {
+            fee_lamports += TOKEN_ACCOUNT_RENT_LAMPORTS;
+        }
+
+        fee_lamports as f64 / LAMPORTS_PER_SOL as f64
+    }
+
     /// Update buy tracking
     async fn update_buy_tracking(&self) {
         let now = std::time::SystemTime::now()
@@ -263,42 +709,186 @@ impl Trader {
         }
     }
 
-    /// Create a new position after successful buy
-    async fn create_position(&self, analysis: &TokenAnalysis, signature: String) {
+    /// Tokens a buy of `amount_sol` should receive at the analyzed price, net of the protocol fee
+    async fn expected_token_amount(
+        &self,
+        analysis: &TokenAnalysis,
+        amount_sol: f64,
+    ) -> Result<u64, Box<dyn std::error::Error>> {
+        let fee_bps = self.pump_fee_bps().await?;
+        Ok(curve_token_amount(&analysis.bonding_curve, amount_sol, fee_bps))
+    }
+
+    /// SOL currently committed to an open position in `mint`, at entry price
+    async fn mint_exposure_sol(&self, mint: &str) -> f64 {
+        self.positions.read().await
+            .get(mint)
+            .filter(|position| position.status != PositionStatus::Closed)
+            .map_or(0.0, |position| position.amount as f64 * position.entry_price)
+    }
+
+    /// Create a new position after successful buy, or add to an open one
+    async fn create_position(
+        &self,
+        analysis: &TokenAnalysis,
+        signature: String,
+        amount: u64,
+        price: f64,
+    ) {
+        let mut positions = self.positions.write().await;
+
+        // Blend the entry price when adding to an existing position
+        let (amount, entry_price) = match positions
+            .get(&analysis.token.address.to_string())
+            .filter(|position| position.status != PositionStatus::Closed)
+        {
+            Some(existing) => {
+                let total = existing.amount + amount;
+                let cost = existing.amount as f64 * existing.entry_price
+                    + amount as f64 * price;
+                (total, if total > 0 { cost / total as f64 } else { price })
+            }
+            None => (amount, price),
+        };
+
         let position = Position {
             token_address: analysis.token.address,
             token_symbol: analysis.token.symbol.clone(),
-            amount: (self.config.buy_amount_sol * 1_000_000.0) as u64, // Approximate
-            entry_price: analysis.metrics.price,
+            amount,
+            entry_price,
             current_price: analysis.metrics.price,
             pnl: 0.0,
             pnl_percentage: 0.0,
             opened_at: Utc::now(),
             last_updated: Utc::now(),
-            take_profit_price: Some(analysis.metrics.price * (1.0 + self.config.take_profit_percentage / 100.0)),
-            stop_loss_price: Some(analysis.metrics.price * (1.0 - self.config.stop_loss_percentage / 100.0)),
+            take_profit_price: Some(entry_price * (1.0 + self.config.take_profit_percentage / 100.0)),
+            stop_loss_price: Some(entry_price * (1.0 - self.config.stop_loss_percentage / 100.0)),
             trailing_stop_price: None,
             status: PositionStatus::Open,
         };
 
-        self.positions.write().await.insert(
+        positions.insert(
             position.token_address.to_string(),
             position
         );
+        self.persist_positions(&positions);
+    }
+
+    /// Write open positions to the position store, if one is configured
+    fn persist_positions(&self, positions: &HashMap<String, Position>) {
+        if let Some(store) = &self.position_store {
+            if let Err(e) = store.save(positions) {
+                tracing::warn!("Failed to persist positions: {}", e);
+            }
+        }
+    }
+
+    /// Reload persisted positions, keeping only those still held on-chain
+    ///
+    /// Recovered amounts are replaced with the wallet's actual token balance.
+    /// Returns the number of positions recovered.
+    pub async fn recover_positions(&self) -> Result<usize, Box<dyn std::error::Error>> {
+        let Some(store) = &self.position_store else {
+            return Ok(0);
+        };
+
+        let persisted = store.load()?;
+
+        // Reconcile every persisted mint against the wallet in batched lookups
+        let held_balances = if self.config.simulation_mode {
+            Vec::new()
+        } else {
+            let mints: Vec<Pubkey> = persisted.iter().map(|position| position.token_address).collect();
+            self.client.get_token_balances(&mints).await?
+        };
+
+        let mut recovered = HashMap::new();
+        for (index, mut position) in persisted.into_iter().enumerate() {
+            if !self.config.simulation_mode {
+                let held = held_balances[index];
+                if held == 0 {
+                    tracing::info!(
+                        "Dropping persisted position in {}: no longer held",
+                        position.token_symbol
+                    );
+                    continue;
+                }
+                position.amount = held;
+            }
+
+            recovered.insert(position.token_address.to_string(), position);
+        }
+
+        let mut positions = self.positions.write().await;
+        positions.extend(recovered);
+        self.persist_positions(&positions);
+
+        tracing::info!("Recovered {} open positions", positions.len());
+        Ok(positions.len())
     }
 
     /// Update position after sell
     async fn update_position_after_sell(&self, position: &Position, amount_sold: u64) {
-        let mut positions = self.positions.write().await;
-        if let Some(pos) = positions.get_mut(&position.token_address.to_string()) {
-            pos.amount -= amount_sold;
-            if pos.amount == 0 {
-                pos.status = PositionStatus::Closed;
-            } else {
-                pos.status = PositionStatus::Partial;
+        {
+            let mut positions = self.positions.write().await;
+            if let Some(pos) = positions.get_mut(&position.token_address.to_string()) {
+                pos.amount = pos.amount.saturating_sub(amount_sold);
+                if pos.amount == 0 {
+                    pos.status = PositionStatus::Closed;
+                } else {
+                    pos.status = PositionStatus::Partial;
+                }
+                pos.last_updated = Utc::now();
+
+                *self.realized_pnl.write().await += (pos.current_price - pos.entry_price) * amount_sold as f64;
+            }
+            self.persist_positions(&positions);
+        }
+
+        self.check_drawdown().await;
+    }
+
+    /// Stop trading once realized losses reach the configured drawdown limit
+    async fn check_drawdown(&self) {
+        let realized_pnl = *self.realized_pnl.read().await;
+        if realized_pnl > -self.config.max_drawdown_sol {
+            return;
+        }
+
+        self.activate_kill_switch(&format!(
+            "realized PnL {} SOL breached max drawdown of {} SOL",
+            realized_pnl,
+            self.config.max_drawdown_sol
+        )).await;
+    }
+
+    /// Stop all new buys, returning true if trading was active until now
+    pub async fn activate_kill_switch(&self, reason: &str) -> bool {
+        let mut trading_status = self.trading_status.write().await;
+        if *trading_status != TradingStatus::Active {
+            return false;
+        }
+
+        *trading_status = TradingStatus::Stopped;
+        tracing::error!("Kill switch activated: {}", reason);
+        true
+    }
+
+    /// Sell every open position in full
+    pub async fn liquidate_all(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let positions: Vec<Position> = self.positions.read().await
+            .values()
+            .filter(|position| position.status != PositionStatus::Closed)
+            .cloned()
+            .collect();
+
+        for position in positions {
+            if let Err(e) = self.execute_sell(&position, 100.0, SellReason::Liquidation).await {
+                tracing::error!("Failed to liquidate {}: {}", position.token_symbol, e);
             }
-            pos.last_updated = Utc::now();
         }
+
+        Ok(())
     }
 
     /// Update position price (simplified)
@@ -327,6 +917,11 @@ impl Trader {
         false
     }
 
+    /// Check whether a position has been held for the configured minimum time
+    fn held_min_time(&self, position: &Position) -> bool {
+        (Utc::now() - position.opened_at).num_milliseconds() >= self.config.min_hold_time_ms as i64
+    }
+
     /// Check if position should stop loss
     fn should_stop_loss(&self, position: &Position) -> bool {
         if let Some(sl_price) = position.stop_loss_price {
@@ -335,10 +930,42 @@ impl Trader {
         false
     }
 
+    /// Track how far confirmed buys fell short of their quote
+    ///
+    /// Consistent shortfalls point at mis-pricing in the expected-amount model
+    /// rather than ordinary slippage, so repeated breaches raise an alert.
+    async fn record_realized_slippage(&self, analysis: &TokenAnalysis, expected: u64, received: u64) {
+        let bps = realized_slippage_bps(expected, received);
+
+        let breaches = {
+            let mut realized_slippage = self.realized_slippage.write().await;
+            realized_slippage.last_bps = Some(bps);
+            if bps > self.config.max_realized_slippage_bps {
+                realized_slippage.consecutive_breaches += 1;
+            } else {
+                realized_slippage.consecutive_breaches = 0;
+            }
+            realized_slippage.consecutive_breaches
+        };
+
+        tracing::info!(realized_slippage_bps = bps, "Realized slippage on {}: {} bps", analysis.token.symbol, bps);
+
+        if breaches >= self.config.realized_slippage_breach_limit {
+            tracing::error!(
+                "Realized slippage exceeded {} bps on {} consecutive buys",
+                self.config.max_realized_slippage_bps,
+                breaches
+            );
+
+            if self.config.pause_on_realized_slippage {
+                self.activate_kill_switch("realized slippage repeatedly exceeded the limit").await;
+            }
+        }
+    }
+
     /// Stop the trader
     pub async fn stop(&self) -> Result<(), Box<dyn std::error::Error>> {
         *self.is_buying.write().await = false;
-        *self.is_selling.write().await = false;
         tracing::info!("Trader stopped");
         Ok(())
     }
@@ -347,13 +974,249 @@ impl Trader {
     pub async fn status(&self) -> serde_json::Value {
         let positions_count = self.positions.read().await.len();
         let is_buying = *self.is_buying.read().await;
-        let is_selling = *self.is_selling.read().await;
+        let in_flight_trades = self.config.max_concurrent_trades - self.trade_permits.available_permits();
 
         serde_json::json!({
             "is_buying": is_buying,
-            "is_selling": is_selling,
+            "in_flight_trades": in_flight_trades,
             "active_positions": positions_count,
             "daily_trades": *self.daily_trades.read().await,
+            "realized_pnl": *self.realized_pnl.read().await,
+            "latency_budget_exceeded": *self.latency_budget_exceeded.read().await,
+            "realized_slippage_bps": self.realized_slippage.read().await.last_bps,
+            "partial_fills": *self.partial_fills.read().await,
+            "trading_status": format!("{:?}", *self.trading_status.read().await),
         })
     }
 }
+
+/// Classify a fill of `received` tokens against `expected`
+fn classify_fill(expected: u64, received: u64, min_fill_ratio: f64) -> FillStatus {
+    if received == 0 && expected > 0 {
+        FillStatus::Empty
+    } else if (received as f64) < expected as f64 * min_fill_ratio {
+        FillStatus::Partial
+    } else {
+        FillStatus::Full
+    }
+}
+
+/// Tokens `amount_sol` buys from the analyzed curve, before the protocol fee
+fn quoted_token_amount(analysis: &TokenAnalysis, amount_sol: f64) -> u64 {
+    curve_token_amount(&analysis.bonding_curve, amount_sol, 0)
+}
+
+/// Tokens the constant-product curve pays out for `amount_sol`
+///
+/// The fee is taken from the SOL first, so only `dx` net of it moves the
+/// curve: `virtual_tokens * dx / (virtual_sol + dx)`.
+fn curve_token_amount(bonding_curve: &BondingCurveInfo, amount_sol: f64, fee_bps: u64) -> u64 {
+    let gross_lamports = (amount_sol * LAMPORTS_PER_SOL as f64) as u128;
+    let dx = gross_lamports * 10_000u128.saturating_sub(fee_bps as u128) / 10_000;
+    let virtual_sol = bonding_curve.virtual_sol_reserves as u128;
+    let virtual_tokens = bonding_curve.virtual_token_reserves as u128;
+    if virtual_sol + dx == 0 {
+        return 0;
+    }
+    (virtual_tokens * dx / (virtual_sol + dx)) as u64
+}
+
+/// Shortfall of `received` against `expected`, in basis points
+fn realized_slippage_bps(expected: u64, received: u64) -> u64 {
+    if expected == 0 {
+        return 0;
+    }
+    (expected.saturating_sub(received) as u128 * 10_000 / expected as u128) as u64
+}
+
+/// Check a send error for a Pump.fun slippage failure
+fn is_slippage_error(message: &str) -> bool {
+    PUMP_FUN_SLIPPAGE_ERRORS.iter().any(|error| message.contains(error))
+}
+
+/// Price impact of buying `amount_sol` on the bonding curve, as a percentage
+///
+/// On a constant-product curve the average fill price exceeds the spot price
+/// by `amount_sol / virtual_sol_reserves`.
+fn price_impact_percentage(bonding_curve: &BondingCurveInfo, amount_sol: f64) -> f64 {
+    let sol_reserves = bonding_curve.virtual_sol_reserves as f64 / LAMPORTS_PER_SOL as f64;
+    if sol_reserves <= 0.0 {
+        return 100.0;
+    }
+    amount_sol / sol_reserves * 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn launch_curve() -> BondingCurveInfo {
+        BondingCurveInfo {
+            address: Pubkey::new_unique(),
+            token_address: Pubkey::new_unique(),
+            virtual_sol_reserves: 30 * LAMPORTS_PER_SOL,
+            virtual_token_reserves: 1_073_000_000_000_000,
+            real_sol_reserves: 0,
+            real_token_reserves: 793_100_000_000_000,
+            token_total_supply: 1_000_000_000_000_000,
+            complete: false,
+        }
+    }
+
+    #[test]
+    fn curve_amount_includes_price_impact() {
+        let curve = launch_curve();
+
+        // 3 SOL into 30 SOL of reserves gets 3/33 of the tokens, not 3/30
+        let amount = curve_token_amount(&curve, 3.0, 0);
+        assert_eq!(amount, 1_073_000_000_000_000 * 3 / 33);
+
+        let spot = (3.0 / (30.0 / 1.073e15)) as u64;
+        assert!(amount < spot);
+    }
+
+    #[test]
+    fn curve_amount_nets_the_fee() {
+        let curve = launch_curve();
+
+        // 1% of 1 SOL goes to the fee, leaving 0.99 SOL for the curve
+        let dx = 990_000_000u128;
+        let expected = (1_073_000_000_000_000u128 * dx / (30 * LAMPORTS_PER_SOL as u128 + dx)) as u64;
+        assert_eq!(curve_token_amount(&curve, 1.0, 100), expected);
+        assert!(curve_token_amount(&curve, 1.0, 100) < curve_token_amount(&curve, 1.0, 0));
+    }
+
+    #[test]
+    fn curve_amount_of_nothing_is_zero() {
+        assert_eq!(curve_token_amount(&launch_curve(), 0.0, 100), 0);
+    }
+
+    #[test]
+    fn fill_with_nothing_received_is_empty() {
+        assert_eq!(classify_fill(1_000, 0, 0.5), FillStatus::Empty);
+        assert_eq!(classify_fill(1_000, 0, 0.0), FillStatus::Empty);
+    }
+
+    #[test]
+    fn fill_below_the_ratio_is_partial() {
+        assert_eq!(classify_fill(1_000, 499, 0.5), FillStatus::Partial);
+        assert_eq!(classify_fill(1_000, 1, 0.5), FillStatus::Partial);
+    }
+
+    #[test]
+    fn fill_at_or_above_the_ratio_is_full() {
+        assert_eq!(classify_fill(1_000, 500, 0.5), FillStatus::Full);
+        assert_eq!(classify_fill(1_000, 1_000, 0.5), FillStatus::Full);
+        assert_eq!(classify_fill(1_000, 1_200, 0.5), FillStatus::Full);
+    }
+
+    #[test]
+    fn fill_ratio_edges() {
+        // A ratio of 1 demands every expected token, a ratio of 0 accepts any tokens
+        assert_eq!(classify_fill(1_000, 999, 1.0), FillStatus::Partial);
+        assert_eq!(classify_fill(1_000, 1_000, 1.0), FillStatus::Full);
+        assert_eq!(classify_fill(1_000, 1, 0.0), FillStatus::Full);
+    }
+
+    #[test]
+    fn fill_with_nothing_expected_is_full() {
+        assert_eq!(classify_fill(0, 0, 0.5), FillStatus::Full);
+    }
+
+    #[test]
+    fn slippage_bps_of_shortfall() {
+        assert_eq!(realized_slippage_bps(1_000, 1_000), 0);
+        assert_eq!(realized_slippage_bps(1_000, 990), 100);
+        assert_eq!(realized_slippage_bps(1_000, 0), 10_000);
+    }
+
+    #[test]
+    fn slippage_bps_ignores_overfills() {
+        assert_eq!(realized_slippage_bps(1_000, 1_500), 0);
+    }
+
+    #[test]
+    fn slippage_bps_with_nothing_expected_is_zero() {
+        assert_eq!(realized_slippage_bps(0, 0), 0);
+        assert_eq!(realized_slippage_bps(0, 500), 0);
+    }
+
+    #[test]
+    fn slippage_bps_does_not_overflow() {
+        assert_eq!(realized_slippage_bps(u64::MAX, 0), 10_000);
+        assert_eq!(realized_slippage_bps(u64::MAX, u64::MAX / 2), 5_000);
+    }
+
+    #[test]
+    fn price_impact_against_launch_curve() {
+        let curve = launch_curve();
+
+        assert!((price_impact_percentage(&curve, 3.0) - 10.0).abs() < 1e-9);
+        assert!((price_impact_percentage(&curve, 0.3) - 1.0).abs() < 1e-9);
+        assert_eq!(price_impact_percentage(&curve, 0.0), 0.0);
+    }
+
+    #[test]
+    fn price_impact_matches_the_curve_fill() {
+        // The average fill price exceeds spot by exactly the reported impact
+        let curve = launch_curve();
+        let spot_tokens = 1.5 * 1.073e15 / 30.0;
+        let filled = curve_token_amount(&curve, 1.5, 0) as f64;
+
+        let impact = (spot_tokens / filled - 1.0) * 100.0;
+        assert!((impact - price_impact_percentage(&curve, 1.5)).abs() < 1e-6);
+    }
+
+    /// A trader that never reaches the network before the tests finish
+    async fn offline_trader(max_trades_per_minute: u32) -> Trader {
+        let config = Arc::new(BotConfig {
+            rpc_url: "http://127.0.0.1:1".to_string(),
+            max_trades_per_minute,
+            max_trades_per_hour: 1_000,
+            trading_cooldown_ms: 0,
+            ..BotConfig::default()
+        });
+        let client = Arc::new(SolanaClient::new(&config).await.unwrap());
+        Trader::new(client, config).await.unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_reservations_respect_the_limit() {
+        let trader = Arc::new(offline_trader(3).await);
+
+        let reservations: Vec<_> = (0..16)
+            .map(|_| {
+                let trader = Arc::clone(&trader);
+                tokio::spawn(async move { trader.reserve_buy_slot().await })
+            })
+            .collect();
+
+        let mut granted = 0;
+        for reservation in reservations {
+            if reservation.await.unwrap().is_some() {
+                granted += 1;
+            }
+        }
+        assert_eq!(granted, 3);
+        assert!(trader.reserve_buy_slot().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn released_slot_can_be_reserved_again() {
+        let trader = offline_trader(1).await;
+
+        let slot = trader.reserve_buy_slot().await.expect("first slot");
+        assert!(trader.reserve_buy_slot().await.is_none());
+
+        trader.release_buy_slot(slot).await;
+        assert!(trader.reserve_buy_slot().await.is_some());
+    }
+
+    #[test]
+    fn price_impact_without_reserves_is_total() {
+        let mut curve = launch_curve();
+        curve.virtual_sol_reserves = 0;
+
+        assert_eq!(price_impact_percentage(&curve, 0.1), 100.0);
+    }
+}
@@ -127,6 +127,14 @@ pub enum TradeType {
     Sell,
 }
 
+/// Why a position is being sold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SellReason {
+    TakeProfit,
+    StopLoss,
+    Liquidation,
+}
+
 /// Wallet balance
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WalletBalance {
@@ -1,5 +1,8 @@
 pub mod solana_client;
+pub mod blockhash_cache;
+pub mod circuit_breaker;
 pub mod transaction_builder;
 pub mod token_analyzer;
+pub mod slippage_model;
 pub mod safety_checker;
 pub mod wallet_manager;
@@ -0,0 +1,111 @@
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind},
+    rpc_client::RpcClient,
+};
+use solana_sdk::{commitment_config::CommitmentConfig, hash::Hash};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use crate::config::{BotConfig, constants};
+
+/// Cached blockhash with the time it was fetched
+#[derive(Debug, Clone, Copy)]
+struct CachedBlockhash {
+    blockhash: Hash,
+    fetched_at: Instant,
+}
+
+/// Blockhash cache refreshed by a background task
+pub struct BlockhashCache {
+    rpc_client: Arc<RpcClient>,
+    state: Arc<RwLock<Option<CachedBlockhash>>>,
+    refresh_interval: Duration,
+}
+
+impl BlockhashCache {
+    /// Create a new blockhash cache
+    pub fn new(config: &BotConfig) -> Self {
+        let rpc_client = RpcClient::new_with_commitment(
+            config.rpc_url.clone(),
+            CommitmentConfig {
+                commitment: constants::DEFAULT_COMMITMENT,
+            },
+        );
+
+        Self {
+            rpc_client: Arc::new(rpc_client),
+            state: Arc::new(RwLock::new(None)),
+            refresh_interval: Duration::from_millis(config.blockhash_refresh_interval_ms),
+        }
+    }
+
+    /// Start the background refresh task
+    ///
+    /// The task exits on its own once the cache is dropped.
+    pub fn start(&self) {
+        let rpc_client = Arc::clone(&self.rpc_client);
+        let state = Arc::downgrade(&self.state);
+        let refresh_interval = self.refresh_interval;
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(refresh_interval);
+            loop {
+                interval.tick().await;
+
+                let Some(state) = state.upgrade() else {
+                    break;
+                };
+
+                if let Err(e) = Self::refresh_into(&rpc_client, &state).await {
+                    tracing::warn!("Failed to refresh blockhash: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Get the cached blockhash without blocking
+    ///
+    /// Returns `None` if nothing has been fetched yet or the cached
+    /// blockhash is too close to expiry to be used safely.
+    pub fn current(&self) -> Option<Hash> {
+        let cached = (*self.state.read().unwrap())?;
+        if cached.fetched_at.elapsed() >= Duration::from_millis(constants::BLOCKHASH_MAX_AGE_MS) {
+            return None;
+        }
+        Some(cached.blockhash)
+    }
+
+    /// Get a usable blockhash, forcing a refresh if the cached one is missing or stale
+    pub async fn get(&self) -> Result<Hash, Box<dyn std::error::Error>> {
+        if let Some(blockhash) = self.current() {
+            return Ok(blockhash);
+        }
+
+        tracing::debug!("Cached blockhash missing or near expiry, refreshing inline");
+        Self::refresh_into(&self.rpc_client, &self.state)
+            .await
+            .map_err(|e| e as Box<dyn std::error::Error>)
+    }
+
+    /// Fetch the latest blockhash and store it in the cache
+    async fn refresh_into(
+        rpc_client: &Arc<RpcClient>,
+        state: &Arc<RwLock<Option<CachedBlockhash>>>,
+    ) -> Result<Hash, Box<ClientError>> {
+        let rpc_client = Arc::clone(rpc_client);
+        let blockhash = tokio::task::spawn_blocking(move || rpc_client.get_latest_blockhash().map_err(Box::new))
+            .await
+            .map_err(|e| {
+                Box::new(ClientError::from(ClientErrorKind::Custom(format!(
+                    "Blockhash refresh task failed: {}",
+                    e
+                ))))
+            })??;
+
+        *state.write().unwrap() = Some(CachedBlockhash {
+            blockhash,
+            fetched_at: Instant::now(),
+        });
+
+        Ok(blockhash)
+    }
+}
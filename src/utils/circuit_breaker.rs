@@ -0,0 +1,262 @@
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind},
+    rpc_custom_error::{JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED, JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY},
+    rpc_request::RpcError,
+};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use crate::config::{BotConfig, constants::JSON_RPC_INTERNAL_ERROR};
+
+/// Circuit breaker state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// RPC calls flow normally
+    Closed,
+    /// Too many recent failures; calls are refused until the cooldown passes
+    Open,
+    /// Cooldown passed; a single probe call decides whether to close again
+    HalfOpen,
+}
+
+#[derive(Debug)]
+struct BreakerState {
+    state: CircuitState,
+    failures: VecDeque<Instant>,
+    opened_at: Option<Instant>,
+    probe_in_flight: bool,
+}
+
+/// Pauses RPC-dependent work after repeated RPC failures
+///
+/// Opens after `threshold` consecutive failures within `window`, then after
+/// `cooldown` lets one probe through and closes again if it succeeds.
+pub struct RpcCircuitBreaker {
+    inner: Mutex<BreakerState>,
+    threshold: usize,
+    window: Duration,
+    cooldown: Duration,
+}
+
+impl RpcCircuitBreaker {
+    /// Create a circuit breaker from the bot configuration
+    pub fn new(config: &BotConfig) -> Self {
+        Self {
+            inner: Mutex::new(BreakerState {
+                state: CircuitState::Closed,
+                failures: VecDeque::new(),
+                opened_at: None,
+                probe_in_flight: false,
+            }),
+            threshold: config.rpc_failure_threshold as usize,
+            window: Duration::from_secs(config.rpc_failure_window_secs),
+            cooldown: Duration::from_secs(config.rpc_breaker_cooldown_secs),
+        }
+    }
+
+    /// Check whether an RPC-dependent operation may run now
+    ///
+    /// Every `true` must be followed by `record_success` or `record_failure`.
+    pub fn allow(&self) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+
+        match inner.state {
+            CircuitState::Closed => true,
+            CircuitState::Open => {
+                let cooled_down = inner.opened_at.is_some_and(|t| t.elapsed() >= self.cooldown);
+                if cooled_down {
+                    tracing::info!("RPC circuit breaker half-open, probing endpoint");
+                    inner.state = CircuitState::HalfOpen;
+                    inner.probe_in_flight = true;
+                }
+                cooled_down
+            }
+            CircuitState::HalfOpen => {
+                if inner.probe_in_flight {
+                    return false;
+                }
+                inner.probe_in_flight = true;
+                true
+            }
+        }
+    }
+
+    /// Record a successful RPC-dependent operation
+    pub fn record_success(&self) {
+        let mut inner = self.inner.lock().unwrap();
+
+        if inner.state == CircuitState::HalfOpen {
+            tracing::info!("RPC circuit breaker closed, resuming trading");
+        }
+
+        inner.state = CircuitState::Closed;
+        inner.failures.clear();
+        inner.opened_at = None;
+        inner.probe_in_flight = false;
+    }
+
+    /// Record a failed RPC-dependent operation
+    pub fn record_failure(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        let now = Instant::now();
+
+        match inner.state {
+            CircuitState::HalfOpen => {
+                tracing::error!("RPC circuit breaker probe failed, pausing trading again");
+                inner.state = CircuitState::Open;
+                inner.opened_at = Some(now);
+                inner.probe_in_flight = false;
+            }
+            CircuitState::Closed => {
+                inner.failures.push_back(now);
+                while inner.failures.front().is_some_and(|t| now.duration_since(*t) > self.window) {
+                    inner.failures.pop_front();
+                }
+
+                if inner.failures.len() >= self.threshold {
+                    tracing::error!(
+                        "RPC circuit breaker opened after {} failures within {}s, pausing trading",
+                        inner.failures.len(),
+                        self.window.as_secs()
+                    );
+                    inner.state = CircuitState::Open;
+                    inner.opened_at = Some(now);
+                    inner.failures.clear();
+                }
+            }
+            CircuitState::Open => {}
+        }
+    }
+
+    /// Record the outcome of an RPC-dependent operation
+    ///
+    /// Only RPC and transport failures count against the endpoint; any other
+    /// error means the endpoint answered, so it counts as a success.
+    pub fn record_result<T>(&self, result: &Result<T, Box<dyn std::error::Error>>) {
+        match result {
+            Err(e) if is_rpc_failure(e.as_ref()) => self.record_failure(),
+            _ => self.record_success(),
+        }
+    }
+
+    /// Current breaker state
+    pub fn state(&self) -> CircuitState {
+        self.inner.lock().unwrap().state
+    }
+}
+
+/// Check whether an error came from the RPC endpoint or the transport to it
+///
+/// Errors the endpoint returns about the request itself, like a failed
+/// preflight or a missing account, are not endpoint failures.
+pub fn is_rpc_failure(error: &(dyn std::error::Error + 'static)) -> bool {
+    let Some(error) = error.downcast_ref::<ClientError>() else {
+        return false;
+    };
+
+    match error.kind() {
+        ClientErrorKind::Io(_) | ClientErrorKind::Reqwest(_) => true,
+        ClientErrorKind::RpcError(RpcError::RpcRequestError(_)) => true,
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => matches!(
+            *code,
+            JSON_RPC_INTERNAL_ERROR
+                | JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY
+                | JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED
+        ),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker(threshold: u32, cooldown_secs: u64) -> RpcCircuitBreaker {
+        RpcCircuitBreaker::new(&BotConfig {
+            rpc_failure_threshold: threshold,
+            rpc_failure_window_secs: 60,
+            rpc_breaker_cooldown_secs: cooldown_secs,
+            ..BotConfig::default()
+        })
+    }
+
+    #[test]
+    fn opens_after_threshold_failures() {
+        let breaker = breaker(3, 60);
+
+        breaker.record_failure();
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert!(breaker.allow());
+
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(!breaker.allow());
+    }
+
+    #[test]
+    fn success_resets_failure_count() {
+        let breaker = breaker(2, 60);
+
+        breaker.record_failure();
+        breaker.record_success();
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn half_opens_after_cooldown_with_a_single_probe() {
+        let breaker = breaker(1, 0);
+
+        breaker.record_failure();
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        assert!(breaker.allow());
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        assert!(!breaker.allow());
+    }
+
+    #[test]
+    fn probe_success_closes() {
+        let breaker = breaker(1, 0);
+
+        breaker.record_failure();
+        assert!(breaker.allow());
+        breaker.record_success();
+
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert!(breaker.allow());
+    }
+
+    #[test]
+    fn probe_failure_reopens() {
+        let breaker = breaker(1, 60);
+
+        breaker.record_failure();
+        // Skip the cooldown by marking the probe as started
+        {
+            let mut inner = breaker.inner.lock().unwrap();
+            inner.state = CircuitState::HalfOpen;
+            inner.probe_in_flight = true;
+        }
+        breaker.record_failure();
+
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(!breaker.allow());
+    }
+
+    #[test]
+    fn only_rpc_errors_count_as_failures() {
+        let breaker = breaker(1, 60);
+
+        let rejected: Result<(), Box<dyn std::error::Error>> = Err("not a mint".into());
+        breaker.record_result(&rejected);
+        assert_eq!(breaker.state(), CircuitState::Closed);
+
+        let transport: Result<(), Box<dyn std::error::Error>> = Err(Box::new(ClientError::from(
+            ClientErrorKind::RpcError(RpcError::RpcRequestError("connection reset".to_string())),
+        )));
+        breaker.record_result(&transport);
+        assert_eq!(breaker.state(), CircuitState::Open);
+    }
+}
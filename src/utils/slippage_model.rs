@@ -0,0 +1,73 @@
+use crate::config::BotConfig;
+
+/// Slippage tolerance that scales with trade size relative to liquidity
+///
+/// Small trades into deep pools get a tight tolerance, large trades into
+/// thin pools get more room, never exceeding the configured maximum.
+#[derive(Debug, Clone, Copy)]
+pub struct SlippageModel {
+    base_percentage: f64,
+    size_factor: f64,
+    max_percentage: f64,
+}
+
+impl SlippageModel {
+    /// Create a slippage model from the bot configuration
+    pub fn from_config(config: &BotConfig) -> Self {
+        Self {
+            base_percentage: config.base_slippage,
+            size_factor: config.slippage_size_factor,
+            max_percentage: config.max_slippage,
+        }
+    }
+
+    /// Slippage percentage for a trade of `size_sol` against `liquidity_sol`
+    pub fn slippage_for(&self, size_sol: f64, liquidity_sol: f64) -> f64 {
+        if liquidity_sol <= 0.0 {
+            return self.max_percentage;
+        }
+
+        let slippage = self.base_percentage + self.size_factor * (size_sol / liquidity_sol);
+        slippage.min(self.max_percentage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model() -> SlippageModel {
+        SlippageModel {
+            base_percentage: 1.0,
+            size_factor: 50.0,
+            max_percentage: 10.0,
+        }
+    }
+
+    #[test]
+    fn stays_within_base_and_max() {
+        let model = model();
+
+        assert_eq!(model.slippage_for(0.0, 30.0), 1.0);
+        assert_eq!(model.slippage_for(30.0, 30.0), 10.0);
+        assert_eq!(model.slippage_for(1_000.0, 1.0), 10.0);
+    }
+
+    #[test]
+    fn grows_with_trade_size() {
+        let model = model();
+
+        let sizes = [0.01, 0.1, 0.5, 1.0, 2.0, 5.0, 10.0];
+        let slippages: Vec<f64> = sizes.iter().map(|&size| model.slippage_for(size, 30.0)).collect();
+        assert!(slippages.windows(2).all(|pair| pair[0] <= pair[1]), "{:?}", slippages);
+        assert!((model.slippage_for(0.3, 30.0) - 1.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn empty_liquidity_uses_max() {
+        let model = model();
+
+        assert_eq!(model.slippage_for(0.1, 0.0), 10.0);
+        assert_eq!(model.slippage_for(0.1, -5.0), 10.0);
+    }
+}
@@ -1,20 +1,44 @@
-use solana_client::rpc_client::RpcClient;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use solana_account_decoder::UiAccountData;
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind},
+    rpc_client::RpcClient,
+    rpc_config::{RpcSendTransactionConfig, RpcTransactionConfig},
+    rpc_request::{RpcError, RpcResponseErrorData, TokenAccountsFilter},
+    rpc_response::RpcKeyedAccount,
+};
 use solana_sdk::{
     commitment_config::CommitmentConfig,
+    hash::Hash,
+    program_pack::Pack,
     pubkey::Pubkey,
-    signature::{Keypair, Signer},
+    signature::{Keypair, Signature, Signer},
     transaction::Transaction,
     system_instruction,
     native_token::LAMPORTS_PER_SOL,
 };
-use std::sync::Arc;
-use crate::config::{BotConfig, constants};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use serde_json::json;
+use crate::{
+    config::{BotConfig, constants},
+    utils::blockhash_cache::BlockhashCache,
+};
 
 /// Solana client wrapper for the bot
 pub struct SolanaClient {
     rpc_client: RpcClient,
+    simulation_rpc_client: Option<RpcClient>,
+    blockhash_cache: BlockhashCache,
     keypair: Option<Keypair>,
     main_keypair: Option<Keypair>,
+    dump_failed_tx_dir: Option<PathBuf>,
+    http_client: reqwest::Client,
+    priority_fee_api_url: Option<String>,
+    min_priority_fee: u64,
+    max_priority_fee: u64,
+    confirmation_timeout: Duration,
+    confirmation_poll_interval: Duration,
 }
 
 impl SolanaClient {
@@ -29,6 +53,14 @@ impl SolanaClient {
             commitment_config,
         );
 
+        // Keep heavy simulation traffic off the execution endpoint
+        let simulation_rpc_client = config.simulation_rpc_url.as_ref()
+            .map(|url| RpcClient::new_with_commitment(url.clone(), commitment_config));
+
+        // Keep a fresh blockhash off the send path
+        let blockhash_cache = BlockhashCache::new(config);
+        blockhash_cache.start();
+
         // Initialize keypairs
         let keypair = if let Some(private_key) = &config.private_key {
             Some(Self::keypair_from_base58(private_key)?)
@@ -44,8 +76,19 @@ impl SolanaClient {
 
         Ok(Self {
             rpc_client,
+            simulation_rpc_client,
+            blockhash_cache,
             keypair,
             main_keypair,
+            dump_failed_tx_dir: config.dump_failed_tx_dir.as_ref().map(PathBuf::from),
+            http_client: reqwest::Client::builder()
+                .timeout(Duration::from_millis(constants::PRIORITY_FEE_API_TIMEOUT_MS))
+                .build()?,
+            priority_fee_api_url: config.priority_fee_api_url.clone(),
+            min_priority_fee: config.priority_fee_lamports,
+            max_priority_fee: config.max_priority_fee_lamports,
+            confirmation_timeout: Duration::from_millis(config.confirmation_timeout_ms),
+            confirmation_poll_interval: Duration::from_millis(config.confirmation_poll_interval_ms),
         })
     }
 
@@ -84,50 +127,385 @@ impl SolanaClient {
         self.get_balance(&pubkey).await
     }
 
+    /// Get the trading wallet's raw token balance for a mint
+    ///
+    /// Returns 0 if the associated token account does not exist.
+    pub async fn get_token_balance(&self, mint: &Pubkey) -> Result<u64, Box<dyn std::error::Error>> {
+        let owner = self.public_key()?;
+        let token_account = spl_associated_token_account::get_associated_token_address(&owner, mint);
+
+        match self.rpc_client.get_token_account(&token_account)? {
+            Some(account) => Ok(account.token_amount.amount.parse()?),
+            None => Ok(0),
+        }
+    }
+
+    /// Close the trading wallet's empty token accounts and any WSOL account
+    ///
+    /// Rent and wrapped SOL are returned to the wallet. Closes are batched
+    /// into as few transactions as possible. Returns the number of accounts closed.
+    pub async fn close_empty_token_accounts(&self) -> Result<usize, Box<dyn std::error::Error>> {
+        let keypair = self.keypair.as_ref()
+            .ok_or("No trading wallet configured")?;
+        let owner = keypair.pubkey();
+
+        let accounts = self.rpc_client.get_token_accounts_by_owner(
+            &owner,
+            TokenAccountsFilter::ProgramId(spl_token::id()),
+        )?;
+
+        let closable: Vec<Pubkey> = accounts
+            .iter()
+            .filter(|account| Self::is_closable_token_account(account))
+            .filter_map(|account| account.pubkey.parse().ok())
+            .collect();
+
+        for batch in closable.chunks(constants::CLOSE_ACCOUNTS_PER_TX) {
+            let instructions = batch
+                .iter()
+                .map(|account| {
+                    spl_token::instruction::close_account(&spl_token::id(), account, &owner, &owner, &[])
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let mut transaction = Transaction::new_with_payer(&instructions, Some(&owner));
+            let recent_blockhash = self.blockhash_cache.get().await?;
+            transaction.sign(&[keypair], recent_blockhash);
+
+            let signature = self.rpc_client.send_transaction(&transaction)?;
+            self.confirm_transaction(&signature, &recent_blockhash).await?;
+            tracing::info!("Closed {} token accounts: {}", batch.len(), signature);
+        }
+
+        Ok(closable.len())
+    }
+
+    /// A token account can be closed when it is empty or holds wrapped SOL
+    fn is_closable_token_account(account: &RpcKeyedAccount) -> bool {
+        let UiAccountData::Json(parsed) = &account.account.data else {
+            return false;
+        };
+        let info = &parsed.parsed["info"];
+
+        info["mint"].as_str() == Some(&spl_token::native_mint::id().to_string())
+            || info["tokenAmount"]["amount"].as_str() == Some("0")
+    }
+
+    /// Get the trading wallet's raw token balances for several mints
+    ///
+    /// Balances are returned in the order of `mints`, fetched with one
+    /// `getMultipleAccounts` call per `MAX_MULTIPLE_ACCOUNTS` mints. Missing
+    /// token accounts count as 0.
+    pub async fn get_token_balances(&self, mints: &[Pubkey]) -> Result<Vec<u64>, Box<dyn std::error::Error>> {
+        let owner = self.public_key()?;
+        let token_accounts: Vec<Pubkey> = mints
+            .iter()
+            .map(|mint| spl_associated_token_account::get_associated_token_address(&owner, mint))
+            .collect();
+
+        let mut balances = Vec::with_capacity(mints.len());
+        for batch in token_accounts.chunks(constants::MAX_MULTIPLE_ACCOUNTS) {
+            for account in self.rpc_client.get_multiple_accounts(batch)? {
+                let amount = match account {
+                    Some(account) => spl_token::state::Account::unpack(&account.data)?.amount,
+                    None => 0,
+                };
+                balances.push(amount);
+            }
+        }
+
+        Ok(balances)
+    }
+
+    /// Read the current protocol fee from the Pump.fun global account
+    pub async fn get_pump_fee_basis_points(&self) -> Result<u64, Box<dyn std::error::Error>> {
+        let account = self.rpc_client.get_account(&constants::PUMP_FUN_GLOBAL)?;
+        if account.owner != constants::PUMP_FUN_PROGRAM_ID {
+            return Err("Pump.fun global account is not owned by the Pump.fun program".into());
+        }
+
+        let offset = constants::PUMP_FUN_GLOBAL_FEE_BPS_OFFSET;
+        let bytes = account.data
+            .get(offset..offset + 8)
+            .ok_or("Pump.fun global account is too short")?;
+
+        Ok(u64::from_le_bytes(bytes.try_into()?))
+    }
+
     /// Get recent blockhash
     pub async fn get_recent_blockhash(&self) -> Result<String, Box<dyn std::error::Error>> {
-        let (blockhash, _) = self.rpc_client.get_recent_blockhash()?;
+        let blockhash = self.blockhash_cache.get().await?;
         Ok(blockhash.to_string())
     }
 
     /// Send a transaction
+    ///
+    /// With `resimulate`, or whenever a dedicated simulation endpoint is
+    /// configured, the signed transaction is simulated first and not sent if
+    /// it would fail against current state.
     pub async fn send_transaction(
         &self,
         mut transaction: Transaction,
+        resimulate: bool,
     ) -> Result<String, Box<dyn std::error::Error>> {
         // Sign the transaction if we have a keypair
         if let Some(keypair) = &self.keypair {
-            let recent_blockhash = self.rpc_client.get_recent_blockhash()?.0;
+            let recent_blockhash = self.blockhash_cache.get().await?;
             transaction.sign(&[keypair], recent_blockhash);
 
-            // Send the transaction
-            let signature = self.rpc_client.send_and_confirm_transaction(&transaction)?;
-            Ok(signature.to_string())
+            // With a dedicated simulation endpoint, every preflight moves there
+            let simulate = resimulate || self.simulation_rpc_client.is_some();
+            let result = self.submit_transaction(&transaction, simulate).await;
+
+            if let Err(e) = &result {
+                if let Some(dir) = &self.dump_failed_tx_dir {
+                    if let Err(dump_err) = self.dump_transaction(dir, &transaction, &e.to_string()) {
+                        tracing::warn!("Failed to dump failed transaction: {}", dump_err);
+                    }
+                }
+            }
+
+            result
         } else {
             Err("No trading wallet configured for signing".into())
         }
     }
 
+    /// Send a signed transaction and wait for it to land
+    ///
+    /// An explicit simulation replaces the node's preflight check rather than
+    /// running alongside it.
+    async fn submit_transaction(
+        &self,
+        transaction: &Transaction,
+        simulate: bool,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        if simulate {
+            self.check_simulation(transaction)?;
+        }
+
+        let config = RpcSendTransactionConfig {
+            skip_preflight: simulate,
+            preflight_commitment: Some(self.rpc_client.commitment().commitment),
+            ..RpcSendTransactionConfig::default()
+        };
+        let signature = self.rpc_client
+            .send_transaction_with_config(transaction, config)
+            .map_err(Self::describe_send_error)?;
+
+        self.confirm_transaction(&signature, &transaction.message.recent_blockhash).await?;
+        Ok(signature.to_string())
+    }
+
+    /// Poll a sent transaction until it confirms, fails or its blockhash expires
+    ///
+    /// A transaction can still land until its blockhash expires, so past the
+    /// confirmation timeout polling continues while the blockhash is valid.
+    async fn confirm_transaction(
+        &self,
+        signature: &Signature,
+        blockhash: &Hash,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let started = Instant::now();
+        let hard_limit = self.confirmation_timeout + Duration::from_millis(constants::BLOCKHASH_LIFETIME_MS);
+
+        loop {
+            // Checked before the status so a last-moment landing is still seen
+            let expired = started.elapsed() >= self.confirmation_timeout
+                && !self.rpc_client.is_blockhash_valid(blockhash, self.rpc_client.commitment())?;
+            let timed_out = started.elapsed() >= hard_limit;
+
+            if let Some(status) = self.rpc_client.get_signature_status(signature)? {
+                return status.map_err(|e| self.describe_failed_transaction(signature, &e.to_string()));
+            }
+
+            if expired {
+                return Err(format!(
+                    "Transaction {} expired unconfirmed after {}ms",
+                    signature,
+                    started.elapsed().as_millis()
+                ).into());
+            }
+
+            // The blockhash should be gone by now; the outcome is unknown, not failed
+            if timed_out {
+                return Err(format!(
+                    "Transaction {} status unknown after {}ms: blockhash still reported valid",
+                    signature,
+                    started.elapsed().as_millis()
+                ).into());
+            }
+
+            tokio::time::sleep(self.confirmation_poll_interval).await;
+        }
+    }
+
+    /// Describe a transaction that failed on-chain with its program logs and compute units
+    ///
+    /// The logs carry the program's error message, which is what slippage
+    /// detection matches on; the bare instruction error only has a code.
+    fn describe_failed_transaction(&self, signature: &Signature, error: &str) -> Box<dyn std::error::Error> {
+        match self.fetch_transaction_logs(signature) {
+            Some((compute_units, logs)) => format!(
+                "Transaction {} failed: {} (compute units: {:?})\n  {}",
+                signature,
+                error,
+                compute_units,
+                logs.join("\n  ")
+            ).into(),
+            None => format!("Transaction {} failed: {}", signature, error).into(),
+        }
+    }
+
+    /// Simulate a signed transaction and fail if it would not succeed
+    fn check_simulation(&self, transaction: &Transaction) -> Result<(), Box<dyn std::error::Error>> {
+        let rpc_client = self.simulation_rpc_client.as_ref().unwrap_or(&self.rpc_client);
+        let result = rpc_client.simulate_transaction(transaction)?.value;
+
+        if let Some(err) = result.err {
+            let logs = result.logs.unwrap_or_default().join("\n  ");
+            return Err(format!("Pre-send simulation failed: {:?}\n  {}", err, logs).into());
+        }
+
+        Ok(())
+    }
+
+    /// Describe a failed preflight with its program logs and compute units
+    ///
+    /// Other errors are passed through unchanged so RPC failures stay recognizable.
+    fn describe_send_error(error: ClientError) -> Box<dyn std::error::Error> {
+        if let ClientErrorKind::RpcError(RpcError::RpcResponseError {
+            message,
+            data: RpcResponseErrorData::SendTransactionPreflightFailure(result),
+            ..
+        }) = error.kind()
+        {
+            let logs = result.logs.as_deref().unwrap_or_default().join("\n  ");
+            return format!(
+                "{} (error: {:?}, compute units: {:?})\n  {}",
+                message,
+                result.err,
+                result.units_consumed,
+                logs
+            ).into();
+        }
+
+        error.into()
+    }
+
+    /// Write a failed transaction to the dump directory
+    ///
+    /// `<signature>.b64` holds the base64-encoded transaction and
+    /// `<signature>.log` the error, plus program logs and compute units for
+    /// transactions that failed on-chain.
+    fn dump_transaction(
+        &self,
+        dir: &Path,
+        transaction: &Transaction,
+        error: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::create_dir_all(dir)?;
+        let signature = transaction.signatures[0];
+
+        let encoded = BASE64.encode(bincode::serialize(transaction)?);
+        let path = dir.join(format!("{}.b64", signature));
+        std::fs::write(&path, encoded)?;
+
+        let mut details = format!("error: {}\n", error);
+        if let Some((compute_units, logs)) = self.fetch_transaction_logs(&signature) {
+            details.push_str(&format!(
+                "compute units: {:?}\nlogs:\n  {}\n",
+                compute_units,
+                logs.join("\n  ")
+            ));
+        }
+        std::fs::write(dir.join(format!("{}.log", signature)), details)?;
+
+        tracing::info!("Dumped failed transaction to {}", path.display());
+        Ok(())
+    }
+
+    /// Fetch compute units consumed and program logs of a landed transaction
+    ///
+    /// Returns `None` for transactions that never made it on-chain.
+    fn fetch_transaction_logs(&self, signature: &Signature) -> Option<(Option<u64>, Vec<String>)> {
+        let config = RpcTransactionConfig {
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
+            ..RpcTransactionConfig::default()
+        };
+        let meta = self.rpc_client
+            .get_transaction_with_config(signature, config)
+            .ok()?
+            .transaction
+            .meta?;
+
+        let compute_units: Option<u64> = meta.compute_units_consumed.into();
+        let logs: Option<Vec<String>> = meta.log_messages.into();
+        Some((compute_units, logs.unwrap_or_default()))
+    }
+
     /// Get latest block height
     pub async fn get_latest_block_height(&self) -> Result<u64, Box<dyn std::error::Error>> {
         let block_height = self.rpc_client.get_block_height()?;
         Ok(block_height)
     }
 
-    /// Get priority fee estimate
-    pub async fn get_priority_fee_estimate(&self) -> Result<u64, Box<dyn std::error::Error>> {
+    /// Get priority fee estimate for a transaction touching `accounts`
+    pub async fn get_priority_fee_estimate(&self, accounts: &[Pubkey]) -> Result<u64, Box<dyn std::error::Error>> {
+        // Prefer the provider's recommendation when an API is configured
+        if let Some(url) = &self.priority_fee_api_url {
+            match self.fetch_priority_fee_from_api(url, accounts).await {
+                Ok(fee) => return Ok(fee.clamp(self.min_priority_fee, self.max_priority_fee)),
+                Err(e) => tracing::warn!("Priority fee API failed, falling back to RPC: {}", e),
+            }
+        }
+
         // Get recent priority fees
-        let fees = self.rpc_client.get_recent_prioritization_fees(&[])?;
+        let fees = self.rpc_client.get_recent_prioritization_fees(accounts)?;
 
         if fees.is_empty() {
-            return Ok(10000); // Default fee
+            return Ok(self.min_priority_fee); // Default fee
         }
 
         // Calculate average fee
         let total: u64 = fees.iter().map(|fee| fee.prioritization_fee).sum();
         let avg_fee = total / fees.len() as u64;
 
-        Ok(avg_fee.max(10000).min(100000)) // Clamp between min and max
+        // Clamp to the configured range so a fee spike can't blow through the ceiling
+        Ok(avg_fee.clamp(self.min_priority_fee, self.max_priority_fee))
+    }
+
+    /// Request a recommended fee from a Helius-style `getPriorityFeeEstimate` API
+    async fn fetch_priority_fee_from_api(
+        &self,
+        url: &str,
+        accounts: &[Pubkey],
+    ) -> Result<u64, Box<dyn std::error::Error>> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "getPriorityFeeEstimate",
+            "params": [{
+                "accountKeys": accounts.iter().map(Pubkey::to_string).collect::<Vec<_>>(),
+                "options": { "recommended": true },
+            }]
+        });
+
+        let response: serde_json::Value = self.http_client
+            .post(url)
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let estimate = response["result"]["priorityFeeEstimate"]
+            .as_f64()
+            .ok_or_else(|| format!("Unexpected priority fee API response: {}", response))?;
+
+        Ok(estimate as u64)
     }
 
     /// Health check
@@ -170,10 +548,66 @@ impl SolanaClient {
             Some(&from_keypair.pubkey()),
         );
 
-        let recent_blockhash = self.rpc_client.get_recent_blockhash()?.0;
+        let recent_blockhash = self.blockhash_cache.get().await?;
         transaction.sign(&[from_keypair], recent_blockhash);
 
-        let signature = self.rpc_client.send_and_confirm_transaction(&transaction)?;
+        let signature = self.rpc_client.send_transaction(&transaction)?;
+        self.confirm_transaction(&signature, &recent_blockhash).await?;
         Ok(signature.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token_account(mint: &Pubkey, amount: &str) -> RpcKeyedAccount {
+        serde_json::from_value(json!({
+            "pubkey": Pubkey::new_unique().to_string(),
+            "account": {
+                "lamports": 2_039_280,
+                "owner": spl_token::id().to_string(),
+                "executable": false,
+                "rentEpoch": 0,
+                "space": 165,
+                "data": {
+                    "program": "spl-token",
+                    "space": 165,
+                    "parsed": {
+                        "type": "account",
+                        "info": {
+                            "mint": mint.to_string(),
+                            "tokenAmount": { "amount": amount, "decimals": 6 },
+                        },
+                    },
+                },
+            },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn empty_token_account_is_closable() {
+        assert!(SolanaClient::is_closable_token_account(&token_account(&Pubkey::new_unique(), "0")));
+    }
+
+    #[test]
+    fn funded_token_account_is_kept() {
+        assert!(!SolanaClient::is_closable_token_account(&token_account(&Pubkey::new_unique(), "1")));
+    }
+
+    #[test]
+    fn wrapped_sol_account_is_closable_with_a_balance() {
+        let account = token_account(&spl_token::native_mint::id(), "5000000");
+
+        assert!(SolanaClient::is_closable_token_account(&account));
+    }
+
+    #[test]
+    fn unparsed_account_is_kept() {
+        let mut account = token_account(&Pubkey::new_unique(), "0");
+        account.account.data = UiAccountData::LegacyBinary(String::new());
+
+        assert!(!SolanaClient::is_closable_token_account(&account));
+    }
+}
@@ -1,5 +1,5 @@
 use solana_client::rpc_client::RpcClient;
-use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{program_pack::Pack, pubkey::Pubkey};
 use chrono::Utc;
 use crate::{
     config::constants::*,
@@ -21,7 +21,7 @@ impl TokenAnalyzer {
         let token_info = Self::get_token_info(token_address, client).await?;
 
         // Get bonding curve info
-        let bonding_curve = Self::get_bonding_curve_info(bonding_curve_address, client).await?;
+        let bonding_curve = Self::get_bonding_curve_info(bonding_curve_address, token_address, client).await?;
 
         // Calculate metrics
         let metrics = Self::calculate_metrics(&bonding_curve);
@@ -41,6 +41,26 @@ impl TokenAnalyzer {
         })
     }
 
+    /// Check that an address is an initialized legacy SPL token mint
+    ///
+    /// Returns `Ok(false)` for missing accounts or accounts that are not mints,
+    /// so a mis-decoded address can be dropped without counting as an RPC error.
+    /// Pump.fun creates its mints under the legacy token program, so Token-2022
+    /// mints are rejected too: the bonding curve program cannot trade them.
+    pub async fn is_spl_mint(
+        address: &Pubkey,
+        client: &SolanaClient,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let rpc_client = client.rpc_client();
+        let account = rpc_client
+            .get_account_with_commitment(address, rpc_client.commitment())?
+            .value;
+
+        Ok(account.is_some_and(|account| {
+            account.owner == TOKEN_PROGRAM_ID && spl_token::state::Mint::unpack(&account.data).is_ok()
+        }))
+    }
+
     /// Get token information
     async fn get_token_info(
         token_address: &Pubkey,
@@ -67,42 +87,84 @@ impl TokenAnalyzer {
     }
 
     /// Get bonding curve information
+    ///
+    /// Fails rather than falling back to made-up reserves, so nothing is
+    /// ever priced from data that did not come from the chain.
     async fn get_bonding_curve_info(
         bonding_curve_address: &Pubkey,
+        token_address: &Pubkey,
         client: &SolanaClient,
     ) -> Result<BondingCurveInfo, Box<dyn std::error::Error>> {
-        // Get bonding curve account info (simplified)
-        // In a real implementation, you'd decode the bonding curve data
+        let account = client.rpc_client().get_account(bonding_curve_address)?;
+
+        if account.owner != PUMP_FUN_PROGRAM_ID {
+            return Err(format!(
+                "Bonding curve {} is owned by {}, not the Pump.fun program",
+                bonding_curve_address, account.owner
+            ).into());
+        }
+
+        Self::decode_bonding_curve(bonding_curve_address, token_address, &account.data)
+    }
+
+    /// Decode a Pump.fun bonding curve account
+    ///
+    /// Layout: discriminator, five little-endian u64 reserve/supply fields,
+    /// then the `complete` flag.
+    fn decode_bonding_curve(
+        bonding_curve_address: &Pubkey,
+        token_address: &Pubkey,
+        data: &[u8],
+    ) -> Result<BondingCurveInfo, Box<dyn std::error::Error>> {
+        let payload = data
+            .strip_prefix(PUMP_FUN_BONDING_CURVE_DISCRIMINATOR.as_slice())
+            .ok_or_else(|| format!("Account {} is not a Pump.fun bonding curve", bonding_curve_address))?;
+
+        if payload.len() < 41 {
+            return Err(format!(
+                "Bonding curve {} data too short: {} bytes after the discriminator",
+                bonding_curve_address,
+                payload.len()
+            ).into());
+        }
+
+        let read_u64 = |index: usize| {
+            let start = index * 8;
+            u64::from_le_bytes(payload[start..start + 8].try_into().unwrap())
+        };
 
         let bonding_curve = BondingCurveInfo {
             address: *bonding_curve_address,
-            token_address: Pubkey::new_unique(), // Would be decoded
-            virtual_sol_reserves: LAMPORTS_PER_SOL, // 1 SOL
-            virtual_token_reserves: 1_000_000_000, // Placeholder
-            real_sol_reserves: 0,
-            real_token_reserves: 0,
-            token_total_supply: 1_000_000_000, // Placeholder
-            complete: false,
+            token_address: *token_address,
+            virtual_token_reserves: read_u64(0),
+            virtual_sol_reserves: read_u64(1),
+            real_token_reserves: read_u64(2),
+            real_sol_reserves: read_u64(3),
+            token_total_supply: read_u64(4),
+            complete: payload[40] != 0,
         };
 
+        if bonding_curve.virtual_sol_reserves == 0 || bonding_curve.virtual_token_reserves == 0 {
+            return Err(format!("Bonding curve {} has empty virtual reserves", bonding_curve_address).into());
+        }
+
         Ok(bonding_curve)
     }
 
     /// Calculate token metrics
     fn calculate_metrics(bonding_curve: &BondingCurveInfo) -> TokenMetrics {
-        // Calculate price based on bonding curve formula
+        // Spot price of the constant-product curve; the virtual reserves
+        // already include the real ones
         let virtual_sol = bonding_curve.virtual_sol_reserves as f64 / LAMPORTS_PER_SOL as f64;
         let virtual_tokens = bonding_curve.virtual_token_reserves as f64;
-        let real_sol = bonding_curve.real_sol_reserves as f64 / LAMPORTS_PER_SOL as f64;
-        let real_tokens = bonding_curve.real_token_reserves as f64;
 
-        let price = (virtual_sol + real_sol) / (virtual_tokens - real_tokens).max(1.0);
+        let price = virtual_sol / virtual_tokens.max(1.0);
 
         // Calculate market cap
         let market_cap = price * bonding_curve.token_total_supply as f64;
 
-        // Calculate liquidity
-        let liquidity = virtual_sol + real_sol;
+        // Depth the curve prices against, which is what sizing and slippage care about
+        let liquidity = virtual_sol;
 
         TokenMetrics {
             market_cap,
@@ -212,3 +274,61 @@ pub async fn analyze_token(
 ) -> Result<TokenAnalysis, Box<dyn std::error::Error>> {
     TokenAnalyzer::analyze_token(token_address, bonding_curve_address, client).await
 }
+
+/// Convenience function for validating mints
+pub async fn is_spl_mint(
+    address: &Pubkey,
+    client: &SolanaClient,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    TokenAnalyzer::is_spl_mint(address, client).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn launch_curve() -> BondingCurveInfo {
+        BondingCurveInfo {
+            address: Pubkey::new_unique(),
+            token_address: Pubkey::new_unique(),
+            virtual_sol_reserves: 30 * LAMPORTS_PER_SOL,
+            virtual_token_reserves: 1_073_000_000_000_000,
+            real_sol_reserves: 0,
+            real_token_reserves: 793_100_000_000_000,
+            token_total_supply: 1_000_000_000_000_000,
+            complete: false,
+        }
+    }
+
+    #[test]
+    fn metrics_for_launch_curve() {
+        let metrics = TokenAnalyzer::calculate_metrics(&launch_curve());
+
+        let expected_price = 30.0 / 1.073e15;
+        assert!((metrics.price - expected_price).abs() / expected_price < 1e-9);
+        assert!((metrics.market_cap - expected_price * 1e15).abs() < 1e-9);
+        assert!((metrics.liquidity - 30.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn short_bonding_curve_reports_payload_length() {
+        let mut data = PUMP_FUN_BONDING_CURVE_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&[0u8; 16]);
+
+        let error = TokenAnalyzer::decode_bonding_curve(&Pubkey::new_unique(), &Pubkey::new_unique(), &data)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("16 bytes"), "{}", error);
+    }
+
+    #[test]
+    fn metrics_ignore_real_reserves() {
+        let mut curve = launch_curve();
+        curve.real_sol_reserves = 5 * LAMPORTS_PER_SOL;
+
+        let metrics = TokenAnalyzer::calculate_metrics(&curve);
+
+        assert!((metrics.liquidity - 30.0).abs() < 1e-9);
+        assert!((metrics.price - 30.0 / 1.073e15).abs() / metrics.price < 1e-9);
+    }
+}
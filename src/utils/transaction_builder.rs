@@ -31,11 +31,12 @@ impl TransactionBuilder {
         token_address: &Pubkey,
         bonding_curve_address: &Pubkey,
         amount_sol: f64,
+        fee_bps: u64,
         slippage_percentage: f64,
     ) -> Result<solana_sdk::transaction::Transaction, Box<dyn std::error::Error>> {
         // Calculate amounts
         let amount_lamports = (amount_sol * crate::config::constants::LAMPORTS_PER_SOL as f64) as u64;
-        let max_sol_cost = ((amount_lamports as f64) * (1.0 + slippage_percentage / 100.0)) as u64;
+        let max_sol_cost = max_sol_cost(amount_lamports, fee_bps, slippage_percentage);
 
         // Get associated bonding curve
         let associated_bonding_curve = self.find_associated_token_address(
@@ -52,7 +53,11 @@ impl TransactionBuilder {
         };
 
         // Get priority fee
-        let priority_fee = self.client.get_priority_fee_estimate().await?;
+        let priority_fee = self.client.get_priority_fee_estimate(&[
+            *token_address,
+            *bonding_curve_address,
+            crate::config::constants::PUMP_FUN_PROGRAM_ID,
+        ]).await?;
 
         // Build instructions
         let mut instructions = Vec::new();
@@ -63,7 +68,7 @@ impl TransactionBuilder {
         );
 
         instructions.push(
-            compute_budget::ComputeBudgetInstruction::set_compute_unit_limit(200_000),
+            compute_budget::ComputeBudgetInstruction::set_compute_unit_limit(crate::config::constants::COMPUTE_UNIT_LIMIT),
         );
 
         // Add buy instruction
@@ -106,8 +111,15 @@ impl TransactionBuilder {
             min_sol_output,
         };
 
-        // Get priority fee
-        let priority_fee = self.client.get_priority_fee_estimate().await?;
+        // Sells use their own fee profile so exits can outbid buys
+        let priority_fee = match self.config.sell_priority_fee_lamports {
+            Some(fee) => fee,
+            None => self.client.get_priority_fee_estimate(&[
+                *token_address,
+                *bonding_curve_address,
+                crate::config::constants::PUMP_FUN_PROGRAM_ID,
+            ]).await?,
+        };
 
         // Build instructions
         let mut instructions = Vec::new();
@@ -118,7 +130,7 @@ impl TransactionBuilder {
         );
 
         instructions.push(
-            compute_budget::ComputeBudgetInstruction::set_compute_unit_limit(200_000),
+            compute_budget::ComputeBudgetInstruction::set_compute_unit_limit(self.config.sell_compute_unit_limit),
         );
 
         // Add sell instruction
@@ -202,8 +214,43 @@ impl TransactionBuilder {
         owner: &Pubkey,
         mint: &Pubkey,
     ) -> Result<Pubkey, Box<dyn std::error::Error>> {
-        // For now, return a placeholder - would need proper derivation
-        // In a real implementation, you'd use spl_associated_token_account::get_associated_token_address
-        Ok(Pubkey::new_unique()) // Placeholder
+        Ok(spl_associated_token_account::get_associated_token_address(owner, mint))
+    }
+}
+
+/// Derive the Pump.fun bonding curve account for a mint
+pub fn bonding_curve_address(mint: &Pubkey) -> Pubkey {
+    use crate::config::constants::*;
+
+    Pubkey::find_program_address(
+        &[BONDING_CURVE_SEED.as_bytes(), mint.as_ref()],
+        &PUMP_FUN_PROGRAM_ID,
+    ).0
+}
+
+/// Most SOL a buy of `amount_lamports` may spend, including the protocol fee
+///
+/// Matches the trader's expected amount: the fee comes out of the amount and
+/// only the remainder moves the curve, then slippage is allowed on the total.
+fn max_sol_cost(amount_lamports: u64, fee_bps: u64, slippage_percentage: f64) -> u64 {
+    let curve_lamports = amount_lamports as u128 * 10_000u128.saturating_sub(fee_bps as u128) / 10_000;
+    let with_fee = curve_lamports + curve_lamports * fee_bps as u128 / 10_000;
+    (with_fee as f64 * (1.0 + slippage_percentage / 100.0)) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn max_sol_cost_without_fee_is_slippage_only() {
+        assert_eq!(max_sol_cost(1_000_000_000, 0, 10.0), 1_100_000_000);
+    }
+
+    #[test]
+    fn max_sol_cost_charges_the_fee_on_the_curve_amount() {
+        // 0.99 SOL into the curve plus 1% fee on it, then 10% slippage
+        let with_fee = 990_000_000f64 + 9_900_000f64;
+        assert_eq!(max_sol_cost(1_000_000_000, 100, 10.0), (with_fee * 1.1) as u64);
     }
 }